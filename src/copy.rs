@@ -0,0 +1,327 @@
+use std::borrow::Borrow;
+use std::sync::Arc;
+
+use crate::{DirError, DirHierarchy, DirMapMulti, DirMapSingle, DirMulti, DirSingle, DirectoryInit, PathTreeInit, TreePath, TreePathBuf};
+use crate::storage::{DirStorage, storage_remove};
+use crate::tree::{DirTarget, join_names, join_path, resolve_parent, resolve_path, resolve_target, validate_name};
+
+// #============================#
+// #=== COPY IMPLEMENTATIONS ===#
+
+impl <T: Clone> DirMulti<T> {
+    /// Recursively copy directory to another location like `cp -r`, returns path of the copy. If the destination is an existing directory,
+    /// the copy is placed inside. Existing empty directory in the way is replaced only if `overwrite` is set.
+    ///
+    /// Every file of the subtree is cloned and the cached locations of the copy are recomputed.
+    /// Use [`DirShared`] to share the subtree between copies instead.
+    pub fn copy_dir(&mut self, source: impl AsRef<TreePath>, destination: impl AsRef<TreePath>, overwrite: bool) -> Result<String, DirError> {
+        let (source_parent, source_name) = resolve_parent(self.get_path(), source.as_ref())?;
+        let destination_names = resolve_path(self.get_path(), destination.as_ref())?;
+        let mut directory = self.descend(&source_parent)?.obtain_dir(source_name)?.clone();
+        let (target_parent, target_name, target) = resolve_target(self, &source_parent, source_name, &destination_names, destination.as_ref(), overwrite)?;
+        if target_parent == source_parent.as_slice() && target_name == source_name { return Err(DirError::InvalidPath(target)) }
+        let parent = self.descend_mut(target_parent)?;
+        directory.reroot(target_name, join_path(parent.get_path(), target_name), parent.get_depth() + 1.0);
+        parent.directory.insert(target_name.to_owned(), directory);
+        Ok(target)
+    }
+}
+impl <T: Clone> DirSingle<T> {
+    /// Recursively copy directory to another location like `cp -r`, returns path of the copy. If the destination is an existing directory,
+    /// the copy is placed inside. Existing empty directory in the way is replaced only if `overwrite` is set.
+    ///
+    /// Every file of the subtree is cloned and the cached locations of the copy are recomputed.
+    pub fn copy_dir(&mut self, source: impl AsRef<TreePath>, destination: impl AsRef<TreePath>, overwrite: bool) -> Result<String, DirError> {
+        let (source_parent, source_name) = resolve_parent(self.get_path(), source.as_ref())?;
+        let destination_names = resolve_path(self.get_path(), destination.as_ref())?;
+        let mut directory = self.descend(&source_parent)?.obtain_dir(source_name)?.clone();
+        let (target_parent, target_name, target) = resolve_target(self, &source_parent, source_name, &destination_names, destination.as_ref(), overwrite)?;
+        if target_parent == source_parent.as_slice() && target_name == source_name { return Err(DirError::InvalidPath(target)) }
+        let parent = self.descend_mut(target_parent)?;
+        directory.reroot(target_name, join_path(parent.get_path(), target_name), parent.get_depth() + 1.0);
+        parent.directory.insert(target_name.to_owned(), directory);
+        Ok(target)
+    }
+}
+
+impl <T: Clone> DirMapMulti<T> {
+    /// Recursively copy directory to another location like `cp -r`, see [`DirMulti::copy_dir`]
+    pub fn copy_dir(&mut self, source: impl AsRef<TreePath>, destination: impl AsRef<TreePath>, overwrite: bool) -> Result<String, DirError> {
+        self.directory.copy_dir(source, destination, overwrite)
+    }
+}
+impl <T: Clone> DirMapSingle<T> {
+    /// Recursively copy directory to another location like `cp -r`, see [`DirSingle::copy_dir`]
+    pub fn copy_dir(&mut self, source: impl AsRef<TreePath>, destination: impl AsRef<TreePath>, overwrite: bool) -> Result<String, DirError> {
+        self.directory.copy_dir(source, destination, overwrite)
+    }
+}
+
+
+
+// #===============================#
+// #=== COPY-ON-WRITE DIRECTORY ===#
+
+/// ## Shared directory
+/// Copy-on-write directory tree. Files and subdirectories are held in [`Arc`], so [`DirShared::copy_dir`] only bumps a reference count
+/// no matter how large the subtree is. Writing through a shared subtree clones just the directories on the way, every one of them
+/// holding the same [`Arc`]s as before, and fixes up their cached name, path and depth.
+///
+/// Until it is written through, a copied directory still caches the location it was copied from, which [`DirShared::get_path`]
+/// and [`DirShared::get_depth`] of a borrowed directory report. Paths given to the methods are resolved from the directory they are called on.
+/// Links are kept but not followed, convert into a [`DirMapMulti`] to resolve them or to use the rest of the API.
+/// ```
+/// # use pathio::prelude::*;
+/// let mut template: PathTree<String> = PathTree::new("Root");
+/// template.insert_file_with_parents("skeleton/config/settings", "default".to_owned()).unwrap();
+///
+/// let mut tree = DirShared::from(template);
+/// tree.create_dir("users").unwrap();
+/// for user in ["alice", "bob"] {
+///     tree.copy_dir("skeleton", format!("users/{user}"), false).unwrap();
+/// }
+/// assert!(std::ptr::eq(tree.borrow_dir("users/alice").unwrap(), tree.borrow_dir("skeleton").unwrap()));
+///
+/// tree.borrow_file_mut("users/bob/config/settings").unwrap().push_str(" for bob");
+/// assert_eq!(tree.borrow_dir("users/bob/config").unwrap().get_path(), "users/bob/config");
+/// assert_eq!(tree.borrow_file("users/alice/config/settings").unwrap(), "default");
+///
+/// let tree = PathTree::from(tree);
+/// assert_eq!(tree.borrow_file("users/bob/config/settings").unwrap(), "default for bob");
+/// ```
+#[derive(Debug)]
+pub struct DirShared<T> {
+    //# SYNC =======
+    name: String,
+    path: String,
+    depth: f32,
+
+    //# DATA =======
+    file: DirStorage<Arc<T>>,
+    directory: DirStorage<Arc<DirShared<T>>>,
+    link: DirStorage<TreePathBuf>,
+}
+impl <T> Clone for DirShared<T> {
+    fn clone(&self) -> Self {
+        DirShared {
+            name: self.name.clone(),
+            path: self.path.clone(),
+            depth: self.depth,
+
+            file: self.file.clone(),
+            directory: self.directory.clone(),
+            link: self.link.clone(),
+        }
+    }
+}
+impl <T> PathTreeInit for DirShared<T> {
+    fn new(name: impl Borrow<str>) -> Self {
+        DirShared {
+            name: name.borrow().to_owned(),
+            path: "".to_owned(),
+            depth: 0.0,
+
+            file: DirStorage::default(),
+            directory: DirStorage::default(),
+            link: DirStorage::default(),
+        }
+    }
+}
+impl <T> DirTarget for DirShared<T> {
+    fn is_vacant(&self) -> bool {
+        self.file.is_empty() && self.directory.is_empty() && self.link.is_empty()
+    }
+
+    fn target_dir(&self, name: &str) -> Option<&Self> {
+        self.directory.get(name).map(|directory| &**directory)
+    }
+
+    fn has_link(&self, name: &str) -> bool {
+        self.link.contains_key(name)
+    }
+}
+impl <T> DirShared<T> {
+    /// Wrap every file and subdirectory of the directory into [`Arc`]
+    fn share(directory: DirMulti<T>) -> Self {
+        let mut shared = DirShared::new(directory.get_name().as_str());
+        shared.path = directory.get_path().to_owned();
+        shared.depth = directory.get_depth();
+        shared.file = directory.file.into_iter().map(|(name, file)| (name, Arc::new(file))).collect();
+        shared.directory = directory.directory.into_iter().map(|(name, directory)| (name, Arc::new(DirShared::share(directory)))).collect();
+        shared.link = directory.link;
+        shared
+    }
+
+    /// Set cached name, path and depth of this directory only, its subdirectories are fixed up once they are written through
+    fn relocate(&mut self, name: &str, path: String, depth: f32) {
+        if self.name != name { self.name = name.to_owned() }
+        self.path = path;
+        self.depth = depth;
+    }
+
+    /// Borrow directory located at the resolved names
+    fn descend(&self, names: &[&str]) -> Result<&DirShared<T>, DirError> {
+        let mut directory = self;
+        for name in names {
+            directory = directory.target_dir(name).ok_or_else(|| DirError::NoDir((*name).to_owned()))?;
+        }
+        Ok(directory)
+    }
+
+    /// Mutably borrow directory located at the resolved names, cloning every directory on the way that is still shared
+    fn descend_mut(&mut self, names: &[&str]) -> Result<&mut DirShared<T>, DirError> {
+        let mut directory = self;
+        for name in names {
+            let (path, depth) = (join_path(&directory.path, name), directory.depth + 1.0);
+            let subdirectory = match directory.directory.get_mut(*name) {
+                Some(subdirectory) => Arc::make_mut(subdirectory),
+                None => return Err(DirError::NoDir((*name).to_owned())),
+            };
+            if subdirectory.path != path || subdirectory.depth != depth || subdirectory.name != *name { subdirectory.relocate(name, path, depth) }
+            directory = subdirectory;
+        }
+        Ok(directory)
+    }
+
+    /// Returns name of the directory
+    pub fn get_name(&self) -> &String {
+        &self.name
+    }
+
+    /// Returns cached depth of the directory
+    pub fn get_depth(&self) -> f32 {
+        self.depth
+    }
+
+    /// Returns cached path of the directory
+    pub fn get_path(&self) -> &String {
+        &self.path
+    }
+
+    /// Create the directory together with every missing parent, returns paths of the created directories
+    pub fn create_dir_all(&mut self, path: impl AsRef<TreePath>) -> Result<Vec<String>, DirError> {
+        let names = resolve_path("", path.as_ref())?;
+        let mut created = Vec::new();
+        for i in 0..names.len() {
+            let directory = self.descend_mut(&names[..i])?;
+            if directory.directory.contains_key(names[i]) { continue }
+            let created_path = join_names(&names[..i], names[i]);
+            validate_name(names[i], &created_path)?;
+            if directory.link.contains_key(names[i]) { return Err(DirError::NameInUse(created_path)) }
+            let mut subdirectory = DirShared::new(names[i]);
+            subdirectory.relocate(names[i], join_path(&directory.path, names[i]), directory.depth + 1.0);
+            directory.directory.insert(names[i].to_owned(), Arc::new(subdirectory));
+            created.push(created_path);
+        }
+        Ok(created)
+    }
+
+    /// Create the directory, its parent has to exist. Returns path of the directory.
+    pub fn create_dir(&mut self, path: impl AsRef<TreePath>) -> Result<String, DirError> {
+        let (names, name) = resolve_parent("", path.as_ref())?;
+        let created_path = join_names(&names, name);
+        if self.descend(&names)?.target_dir(name).is_some() { return Err(DirError::NameInUse(created_path)) }
+        self.create_dir_all(path)?;
+        Ok(created_path)
+    }
+
+    /// Insert file at the path, its parent has to exist
+    pub fn insert_file(&mut self, path: impl AsRef<TreePath>, file: T) -> Result<(), DirError> {
+        let (names, name) = resolve_parent("", path.as_ref())?;
+        validate_name(name, &join_names(&names, name))?;
+        if self.descend(&names)?.file.contains_key(name) || self.descend(&names)?.has_link(name) { return Err(DirError::NameInUse(join_names(&names, name))) }
+        self.descend_mut(&names)?.file.insert(name.to_owned(), Arc::new(file));
+        Ok(())
+    }
+
+    /// Borrow file at the path
+    pub fn borrow_file(&self, path: impl AsRef<TreePath>) -> Result<&T, DirError> {
+        let (names, name) = resolve_parent("", path.as_ref())?;
+        match self.descend(&names)?.file.get(name) {
+            Some(file) => Ok(file),
+            None => Err(DirError::NoFile(name.to_owned())),
+        }
+    }
+
+    /// Borrow directory at the path
+    pub fn borrow_dir(&self, path: impl AsRef<TreePath>) -> Result<&DirShared<T>, DirError> {
+        self.descend(&resolve_path("", path.as_ref())?)
+    }
+
+    /// Mutably borrow directory at the path, cloning every directory on the way that is still shared
+    pub fn borrow_dir_mut(&mut self, path: impl AsRef<TreePath>) -> Result<&mut DirShared<T>, DirError> {
+        self.descend_mut(&resolve_path("", path.as_ref())?)
+    }
+
+    /// Remove directory at the path and return it detached, subtrees it shares with other copies stay shared
+    pub fn remove_dir(&mut self, path: impl AsRef<TreePath>) -> Result<DirShared<T>, DirError> {
+        let (names, name) = resolve_parent("", path.as_ref())?;
+        match storage_remove(&mut self.descend_mut(&names)?.directory, name) {
+            Some(directory) => {
+                let mut directory = Arc::unwrap_or_clone(directory);
+                directory.relocate(name, String::new(), 0.0);
+                Ok(directory)
+            },
+            None => Err(DirError::NoDir(name.to_owned())),
+        }
+    }
+
+    /// Recursively copy directory to another location like `cp -r`, returns path of the copy. If the destination is an existing directory,
+    /// the copy is placed inside. Existing empty directory in the way is replaced only if `overwrite` is set.
+    ///
+    /// The copy shares the whole subtree with the source, nothing is cloned until one of them is written through.
+    pub fn copy_dir(&mut self, source: impl AsRef<TreePath>, destination: impl AsRef<TreePath>, overwrite: bool) -> Result<String, DirError> {
+        let (source_parent, source_name) = resolve_parent("", source.as_ref())?;
+        let destination_names = resolve_path("", destination.as_ref())?;
+        let directory = match self.descend(&source_parent)?.directory.get(source_name) {
+            Some(directory) => Arc::clone(directory),
+            None => return Err(DirError::NoDir(source_name.to_owned())),
+        };
+        let (target_parent, target_name, target) = resolve_target(self, &source_parent, source_name, &destination_names, destination.as_ref(), overwrite)?;
+        if target_parent == source_parent.as_slice() && target_name == source_name { return Err(DirError::InvalidPath(target)) }
+        self.descend_mut(target_parent)?.directory.insert(target_name.to_owned(), directory);
+        Ok(target)
+    }
+}
+impl <T: Clone> DirShared<T> {
+    /// Clone every file and directory that is still shared and rebuild the regular directory
+    fn unshare(self) -> DirMulti<T> {
+        let mut directory = DirMulti::new();
+        directory.file = self.file.into_iter().map(|(name, file)| (name, Arc::unwrap_or_clone(file))).collect();
+        directory.directory = self.directory.into_iter().map(|(name, subdirectory)| (name, Arc::unwrap_or_clone(subdirectory).unshare())).collect();
+        directory.link = self.link;
+        directory
+    }
+
+    /// Mutably borrow file at the path, cloning it and every directory on the way that is still shared
+    pub fn borrow_file_mut(&mut self, path: impl AsRef<TreePath>) -> Result<&mut T, DirError> {
+        let (names, name) = resolve_parent("", path.as_ref())?;
+        match self.descend_mut(&names)?.file.get_mut(name) {
+            Some(file) => Ok(Arc::make_mut(file)),
+            None => Err(DirError::NoFile(name.to_owned())),
+        }
+    }
+
+    /// Remove file at the path, cloning it if other copies still share it
+    pub fn remove_file(&mut self, path: impl AsRef<TreePath>) -> Result<T, DirError> {
+        let (names, name) = resolve_parent("", path.as_ref())?;
+        match storage_remove(&mut self.descend_mut(&names)?.file, name) {
+            Some(file) => Ok(Arc::unwrap_or_clone(file)),
+            None => Err(DirError::NoFile(name.to_owned())),
+        }
+    }
+}
+impl <T> From<DirMapMulti<T>> for DirShared<T> {
+    fn from(value: DirMapMulti<T>) -> Self {
+        DirShared::share(value.directory)
+    }
+}
+impl <T: Clone> From<DirShared<T>> for DirMapMulti<T> {
+    fn from(value: DirShared<T>) -> Self {
+        let name = value.name.to_owned();
+        let mut tree = DirMapMulti::new(name.as_str());
+        tree.directory = value.unshare();
+        tree.directory.reroot(name, String::new(), 0.0);
+        tree
+    }
+}
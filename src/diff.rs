@@ -0,0 +1,163 @@
+use crate::{DirError, DirFiles, DirHierarchy, DirMapMulti, DirMulti, DirectoryInit, TreePathBuf};
+use crate::tree::join_path;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+// #============================#
+// #=== DIFF IMPLEMENTATIONS ===#
+
+/// ## Tree change
+/// Single structural difference between two trees, produced by [`DirMulti::diff`] and replayed by [`DirMulti::apply_patch`].
+///
+/// Paths are relative to the diffed directory. A removed directory is reported once, its content is implied.
+/// An added directory is followed by changes adding its content. Links are compared by their targets and never followed.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum TreeChange<T> {
+    /// Directory exists only in the new tree
+    AddedDir (String),
+
+    /// Directory exists only in the old tree
+    RemovedDir (String),
+
+    /// File exists only in the new tree
+    AddedFile (String, T),
+
+    /// File exists only in the old tree
+    RemovedFile (String),
+
+    /// File exists in both trees but differs, holds the new file
+    ModifiedFile (String, T),
+
+    /// Link exists only in the new tree, holds its target
+    AddedLink (String, TreePathBuf),
+
+    /// Link exists only in the old tree
+    RemovedLink (String),
+
+    /// Link exists in both trees but points elsewhere, holds the new target
+    ModifiedLink (String, TreePathBuf),
+}
+impl <T> TreeChange<T> {
+    /// Returns path of the changed directory or file
+    pub fn path(&self) -> &str {
+        match self {
+            TreeChange::AddedDir(path) | TreeChange::RemovedDir(path) | TreeChange::RemovedFile(path) | TreeChange::RemovedLink(path) => path,
+            TreeChange::AddedFile(path, _) | TreeChange::ModifiedFile(path, _) => path,
+            TreeChange::AddedLink(path, _) | TreeChange::ModifiedLink(path, _) => path,
+        }
+    }
+}
+
+/// Sorted names of the map, so the changes come out in a stable order with any storage backend
+fn sorted_names<'a>(names: impl Iterator<Item = &'a String>) -> Vec<&'a String> {
+    let mut names: Vec<&String> = names.collect();
+    names.sort();
+    names
+}
+
+
+// #===========================#
+// #=== DIR IMPLEMENTATIONS ===#
+
+impl <T: PartialEq + Clone> DirMulti<T> {
+    /// Compute changes turning self into the other directory. Entries are compared level by level in name order,
+    /// parents always come before their content. Removed links come first and added links last on each level,
+    /// so a name can switch between a link and a directory or file.
+    /// ```
+    /// # use pathio::prelude::*;
+    /// let old: PathTree<u32> = [("a/one", 1), ("b/two", 2)].into_iter().collect();
+    /// let new: PathTree<u32> = [("a/one", 10), ("c/three", 3)].into_iter().collect();
+    ///
+    /// let changes = old.diff(&new);
+    /// assert_eq!(changes, vec![
+    ///     TreeChange::ModifiedFile("a/one".to_owned(), 10),
+    ///     TreeChange::RemovedDir("b".to_owned()),
+    ///     TreeChange::AddedDir("c".to_owned()),
+    ///     TreeChange::AddedFile("c/three".to_owned(), 3),
+    /// ]);
+    ///
+    /// let mut patched = old.clone();
+    /// patched.apply_patch(changes).unwrap();
+    /// assert_eq!(patched.diff(&new), vec![]);
+    /// ```
+    pub fn diff(&self, other: &DirMulti<T>) -> Vec<TreeChange<T>> {
+        let mut changes = Vec::new();
+        self.diff_at(other, "", &mut changes);
+        changes
+    }
+
+    /// Recursively record changes between two directories at the same path
+    fn diff_at(&self, other: &DirMulti<T>, path: &str, changes: &mut Vec<TreeChange<T>>) {
+        for name in sorted_names(self.link.keys()) {
+            if !other.link.contains_key(name) { changes.push(TreeChange::RemovedLink(join_path(path, name))) }
+        }
+        for name in sorted_names(self.file.keys()) {
+            if !other.file.contains_key(name) { changes.push(TreeChange::RemovedFile(join_path(path, name))) }
+        }
+        for name in sorted_names(other.file.keys()) {
+            let file = &other.file[name];
+            match self.file.get(name) {
+                Some(old) if old == file => {},
+                Some(_) => changes.push(TreeChange::ModifiedFile(join_path(path, name), file.clone())),
+                None => changes.push(TreeChange::AddedFile(join_path(path, name), file.clone())),
+            }
+        }
+        let names = sorted_names(self.directory.keys().chain(other.directory.keys().filter(|name| !self.directory.contains_key(*name))));
+        for name in names {
+            let directory_path = join_path(path, name);
+            match (self.directory.get(name), other.directory.get(name)) {
+                (Some(old), Some(new)) => old.diff_at(new, &directory_path, changes),
+                (Some(_), None) => changes.push(TreeChange::RemovedDir(directory_path)),
+                (None, Some(new)) => {
+                    changes.push(TreeChange::AddedDir(directory_path.to_owned()));
+                    DirMulti::new().diff_at(new, &directory_path, changes);
+                },
+                (None, None) => {},
+            }
+        }
+        for name in sorted_names(other.link.keys()) {
+            let target = &other.link[name];
+            match self.link.get(name) {
+                Some(old) if old == target => {},
+                Some(_) => changes.push(TreeChange::ModifiedLink(join_path(path, name), target.clone())),
+                None => changes.push(TreeChange::AddedLink(join_path(path, name), target.clone())),
+            }
+        }
+    }
+}
+impl <T> DirMulti<T> {
+    /// Replay changes produced by [`DirMulti::diff`] onto self. Changes are applied in order and the first one
+    /// that doesn't fit the tree is returned as an error, leaving the changes before it applied.
+    pub fn apply_patch(&mut self, changes: impl IntoIterator<Item = TreeChange<T>>) -> Result<(), DirError> {
+        for change in changes {
+            match change {
+                TreeChange::AddedDir(path) => { self.create_dir(path)?; },
+                TreeChange::RemovedDir(path) => { self.remove_dir(path)?; },
+                TreeChange::AddedFile(path, file) => self.insert_file(path, file)?,
+                TreeChange::RemovedFile(path) => { self.remove_file(path)?; },
+                TreeChange::ModifiedFile(path, file) => *self.borrow_file_mut(path)? = file,
+                TreeChange::AddedLink(path, target) => { self.create_link(path, target)?; },
+                TreeChange::RemovedLink(path) => { self.remove_link(path)?; },
+                TreeChange::ModifiedLink(path, target) => {
+                    self.remove_link(&path)?;
+                    self.create_link(path, target)?;
+                },
+            }
+        }
+        Ok(())
+    }
+}
+impl <T: PartialEq + Clone> DirMapMulti<T> {
+    /// Compute changes turning self into the other DirMap, see [`DirMulti::diff`]
+    pub fn diff(&self, other: &DirMapMulti<T>) -> Vec<TreeChange<T>> {
+        self.directory.diff(&other.directory)
+    }
+}
+impl <T> DirMapMulti<T> {
+    /// Replay changes produced by [`DirMulti::diff`] onto self, see [`DirMulti::apply_patch`]
+    pub fn apply_patch(&mut self, changes: impl IntoIterator<Item = TreeChange<T>>) -> Result<(), DirError> {
+        self.directory.apply_patch(changes)
+    }
+}
@@ -0,0 +1,228 @@
+use crate::{DirError, DirHierarchy, DirMapMulti, DirMulti, DirStorage, DirectoryInit, TreePath};
+use crate::tree::{join_names, join_path, resolve_parent};
+use crate::storage::storage_remove;
+
+// #=============================#
+// #=== ENTRY IMPLEMENTATIONS ===#
+
+/// ## Entry
+/// View into a single file slot of a directory, which may be occupied or vacant. Created by [`DirMulti::entry`].
+/// ```
+/// # use pathio::prelude::*;
+/// let mut tree: PathTree<u32> = PathTree::new("Root");
+/// tree.create_dir("counters").unwrap();
+/// for _ in 0..3 {
+///     tree.entry("counters/hits").unwrap().and_modify(|hits| *hits += 1).or_insert(1);
+/// }
+/// assert_eq!(*tree.borrow_file("counters/hits").unwrap(), 3);
+/// ```
+pub enum Entry<'a, T> {
+    /// File with this name exists
+    Occupied (OccupiedEntry<'a, T>),
+
+    /// No file with this name exists
+    Vacant (VacantEntry<'a, T>),
+}
+impl <'a, T> Entry<'a, T> {
+    /// Returns name of the file
+    pub fn key(&self) -> &str {
+        match self {
+            Entry::Occupied(entry) => entry.key(),
+            Entry::Vacant(entry) => entry.key(),
+        }
+    }
+
+    /// Inserts the file if vacant, returns mutable reference to the file in the entry
+    pub fn or_insert(self, file: T) -> &'a mut T {
+        match self {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(file),
+        }
+    }
+
+    /// Inserts file returned by the closure if vacant, returns mutable reference to the file in the entry
+    pub fn or_insert_with(self, f: impl FnOnce() -> T) -> &'a mut T {
+        match self {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(f()),
+        }
+    }
+
+    /// Calls the closure with the file if occupied
+    pub fn and_modify(self, f: impl FnOnce(&mut T)) -> Self {
+        match self {
+            Entry::Occupied(mut entry) => {
+                f(entry.get_mut());
+                Entry::Occupied(entry)
+            },
+            Entry::Vacant(entry) => Entry::Vacant(entry),
+        }
+    }
+}
+impl <'a, T: Default> Entry<'a, T> {
+    /// Inserts default file if vacant, returns mutable reference to the file in the entry
+    pub fn or_default(self) -> &'a mut T {
+        self.or_insert_with(T::default)
+    }
+}
+
+
+/// # Occupied Entry
+/// Slot holding an existing file, part of [`Entry`].
+pub struct OccupiedEntry<'a, T> {
+    storage: &'a mut DirStorage<T>,
+    name: String,
+}
+impl <'a, T> OccupiedEntry<'a, T> {
+    /// Returns name of the file
+    pub fn key(&self) -> &str {
+        &self.name
+    }
+
+    /// Borrow the file
+    pub fn get(&self) -> &T {
+        match self.storage.get(&self.name) {
+            Some(file) => file,
+            None => unreachable!("occupied entry always holds a file"),
+        }
+    }
+
+    /// Mutably borrow the file
+    pub fn get_mut(&mut self) -> &mut T {
+        match self.storage.get_mut(&self.name) {
+            Some(file) => file,
+            None => unreachable!("occupied entry always holds a file"),
+        }
+    }
+
+    /// Convert into mutable reference to the file bound to the directory lifetime
+    pub fn into_mut(self) -> &'a mut T {
+        match self.storage.get_mut(&self.name) {
+            Some(file) => file,
+            None => unreachable!("occupied entry always holds a file"),
+        }
+    }
+
+    /// Replace the file, returns the old one
+    pub fn insert(&mut self, file: T) -> T {
+        std::mem::replace(self.get_mut(), file)
+    }
+
+    /// Remove the file from the directory and return it
+    pub fn remove(self) -> T {
+        match storage_remove(self.storage, &self.name) {
+            Some(file) => file,
+            None => unreachable!("occupied entry always holds a file"),
+        }
+    }
+}
+
+
+/// # Vacant Entry
+/// Empty file slot, part of [`Entry`].
+pub struct VacantEntry<'a, T> {
+    storage: &'a mut DirStorage<T>,
+    name: String,
+}
+impl <'a, T> VacantEntry<'a, T> {
+    /// Returns name of the file
+    pub fn key(&self) -> &str {
+        &self.name
+    }
+
+    /// Insert the file, returns mutable reference to it
+    pub fn insert(self, file: T) -> &'a mut T {
+        self.storage.entry(self.name).or_insert(file)
+    }
+}
+
+
+/// ## Dir Entry
+/// View into a single subdirectory slot of a directory, which may be occupied or vacant. Created by [`DirMulti::dir_entry`].
+///
+/// Inserted directories are re-rooted into their new location.
+pub enum DirEntry<'a, T> {
+    /// Subdirectory with this name exists
+    Occupied (&'a mut DirMulti<T>),
+
+    /// No subdirectory with this name exists, holds the parent directory and the name
+    Vacant (&'a mut DirMulti<T>, String),
+}
+impl <'a, T> DirEntry<'a, T> {
+    /// Inserts the directory if vacant, returns mutable reference to the directory in the entry
+    pub fn or_insert(self, directory: DirMulti<T>) -> &'a mut DirMulti<T> {
+        self.or_insert_with(|| directory)
+    }
+
+    /// Inserts directory returned by the closure if vacant, returns mutable reference to the directory in the entry
+    pub fn or_insert_with(self, f: impl FnOnce() -> DirMulti<T>) -> &'a mut DirMulti<T> {
+        match self {
+            DirEntry::Occupied(directory) => directory,
+            DirEntry::Vacant(parent, name) => {
+                let mut directory = f();
+                directory.reroot(name.as_str(), join_path(parent.get_path(), &name), parent.get_depth() + 1.0);
+                parent.directory.entry(name).or_insert(directory)
+            },
+        }
+    }
+
+    /// Inserts new empty directory if vacant, returns mutable reference to the directory in the entry
+    pub fn or_default(self) -> &'a mut DirMulti<T> {
+        self.or_insert_with(DirMulti::new)
+    }
+
+    /// Calls the closure with the directory if occupied
+    pub fn and_modify(self, f: impl FnOnce(&mut DirMulti<T>)) -> Self {
+        match self {
+            DirEntry::Occupied(directory) => {
+                f(directory);
+                DirEntry::Occupied(directory)
+            },
+            vacant => vacant,
+        }
+    }
+}
+
+
+// #===========================#
+// #=== DIR IMPLEMENTATIONS ===#
+
+impl <T> DirMulti<T> {
+    /// Get entry of the file at the path for in-place manipulation. The path is resolved once and the parent directory has to exist.
+    /// A name used by a link fails with [`DirError::NameInUse`].
+    pub fn entry(&mut self, path: impl AsRef<TreePath>) -> Result<Entry<'_, T>, DirError> {
+        let (names, name) = resolve_parent(self.get_path(), path.as_ref())?;
+        let parent = self.descend_mut(&names)?;
+        if parent.link.contains_key(name) { return Err(DirError::NameInUse(join_names(&names, name))) }
+        let name = name.to_owned();
+        if parent.file.contains_key(&name) {
+            Ok(Entry::Occupied(OccupiedEntry { storage: &mut parent.file, name }))
+        } else {
+            Ok(Entry::Vacant(VacantEntry { storage: &mut parent.file, name }))
+        }
+    }
+
+    /// Get entry of the subdirectory at the path for in-place manipulation. The path is resolved once and the parent directory has to exist.
+    /// A name used by a link fails with [`DirError::NameInUse`].
+    pub fn dir_entry(&mut self, path: impl AsRef<TreePath>) -> Result<DirEntry<'_, T>, DirError> {
+        let (names, name) = resolve_parent(self.get_path(), path.as_ref())?;
+        let parent = self.descend_mut(&names)?;
+        if parent.link.contains_key(name) { return Err(DirError::NameInUse(join_names(&names, name))) }
+        if parent.directory.contains_key(name) {
+            parent.obtain_dir_mut(name).map(DirEntry::Occupied)
+        } else {
+            Ok(DirEntry::Vacant(parent, name.to_owned()))
+        }
+    }
+}
+impl <T> DirMapMulti<T> {
+    /// Get entry of the file at the path for in-place manipulation. The path is resolved once and the parent directory has to exist.
+    pub fn entry(&mut self, path: impl AsRef<TreePath>) -> Result<Entry<'_, T>, DirError> {
+        self.directory.entry(path)
+    }
+
+    /// Get entry of the subdirectory at the path for in-place manipulation. The path is resolved once and the parent directory has to exist.
+    pub fn dir_entry(&mut self, path: impl AsRef<TreePath>) -> Result<DirEntry<'_, T>, DirError> {
+        self.directory.dir_entry(path)
+    }
+}
@@ -0,0 +1,188 @@
+use std::collections::VecDeque;
+use std::ops::{Bound, RangeBounds};
+
+use crate::{DirHierarchy, DirMapMulti, DirMulti, Node, Pattern};
+use crate::tree::join_path;
+
+// #============================#
+// #=== FIND IMPLEMENTATIONS ===#
+
+/// ## Node kind
+/// Type of a node in the tree.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NodeKind {
+    /// Directory
+    Dir,
+
+    /// File
+    File,
+}
+
+
+/// Boxed predicate on a file used by [`Finder`]
+type FilePredicate<'a, T> = Box<dyn Fn(&T) -> bool + 'a>;
+
+/// ## Finder
+/// Builder of a filtered search over a directory and all of its subdirectories, modelled on `find(1)`.
+///
+/// Every predicate narrows down the results, nodes have to pass all of them. The directory the search starts from is not included.
+/// ```
+/// # use pathio::prelude::*;
+/// let tree: PathTree<u32> = [("a/one", 1), ("a/b/two", 2), ("c/three", 3)].into_iter().collect();
+/// let found: Vec<String> = tree.find()
+///     .kind(NodeKind::File)
+///     .depth(2.0..)
+///     .file(|size| *size > 1)
+///     .paths()
+///     .collect();
+/// assert_eq!(found.len(), 2);
+/// ```
+pub struct Finder<'a, T> {
+    root: &'a DirMulti<T>,
+    name: Option<Pattern>,
+    depth: (Bound<f32>, Bound<f32>),
+    kind: Option<NodeKind>,
+    file: Option<FilePredicate<'a, T>>,
+    empty: bool,
+    limit: Option<usize>,
+}
+impl <'a, T> Finder<'a, T> {
+    pub(crate) fn new(root: &'a DirMulti<T>) -> Self {
+        Finder { root, name: None, depth: (Bound::Unbounded, Bound::Unbounded), kind: None, file: None, empty: false, limit: None }
+    }
+
+    /// Only match nodes whose name matches the glob pattern
+    pub fn name(mut self, pattern: Pattern) -> Self {
+        self.name = Some(pattern);
+        self
+    }
+
+    /// Only match nodes whose cached depth is in the range, files are one level deeper than their directory.
+    /// Subdirectories beyond the upper bound are not visited.
+    pub fn depth(mut self, range: impl RangeBounds<f32>) -> Self {
+        self.depth = (range.start_bound().cloned(), range.end_bound().cloned());
+        self
+    }
+
+    /// Only match nodes of the given kind
+    pub fn kind(mut self, kind: NodeKind) -> Self {
+        self.kind = Some(kind);
+        self
+    }
+
+    /// Only match files passing the predicate, directories are skipped
+    pub fn file(mut self, predicate: impl Fn(&T) -> bool + 'a) -> Self {
+        self.file = Some(Box::new(predicate));
+        self
+    }
+
+    /// Only match directories without any files, links and subdirectories
+    pub fn empty(mut self) -> Self {
+        self.empty = true;
+        self
+    }
+
+    /// Stop after the given number of results
+    pub fn limit(mut self, limit: usize) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Lazily run the search, yielding `(path, node)` with paths relative to the starting directory
+    pub fn iter(self) -> Find<'a, T> {
+        let remaining = self.limit.unwrap_or(usize::MAX);
+        Find { stack: vec![(String::new(), self.root)], buffer: VecDeque::new(), remaining, finder: self }
+    }
+
+    /// Lazily run the search, yielding only the paths
+    pub fn paths(self) -> impl Iterator<Item = String> + 'a {
+        self.iter().map(|(path, _)| path)
+    }
+
+    /// Check if the node passes all predicates
+    fn accepts(&self, name: &str, depth: f32, node: &Node<'a, T>) -> bool {
+        if let Some(pattern) = &self.name {
+            if !pattern.matches(name) { return false }
+        }
+        if !self.depth.contains(&depth) { return false }
+        match node {
+            Node::Dir(directory) => {
+                self.kind != Some(NodeKind::File) && self.file.is_none() &&
+                (!self.empty || (directory.file.is_empty() && directory.directory.is_empty() && directory.link.is_empty()))
+            },
+            Node::File(file) => {
+                self.kind != Some(NodeKind::Dir) && !self.empty &&
+                self.file.as_ref().is_none_or(|predicate| predicate(file))
+            },
+        }
+    }
+
+    /// Check if anything below the given depth can still match
+    fn descends(&self, depth: f32) -> bool {
+        match self.depth.1 {
+            Bound::Included(max) => depth + 1.0 <= max,
+            Bound::Excluded(max) => depth + 1.0 < max,
+            Bound::Unbounded => true,
+        }
+    }
+}
+impl <'a, T> IntoIterator for Finder<'a, T> {
+    type Item = (String, Node<'a, T>);
+    type IntoIter = Find<'a, T>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+
+/// # Find
+/// Lazy iterator running the search configured by a [`Finder`].
+///
+/// Yields `(path, node)` pairs with paths relative to the starting directory. Directories are visited depth-first.
+pub struct Find<'a, T> {
+    finder: Finder<'a, T>,
+    stack: Vec<(String, &'a DirMulti<T>)>,
+    buffer: VecDeque<(String, Node<'a, T>)>,
+    remaining: usize,
+}
+impl <'a, T> Iterator for Find<'a, T> {
+    type Item = (String, Node<'a, T>);
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if self.remaining == 0 { return None }
+            if let Some(item) = self.buffer.pop_front() {
+                self.remaining -= 1;
+                return Some(item)
+            }
+            let (path, directory) = self.stack.pop()?;
+            let depth = directory.get_depth();
+            if !self.finder.descends(depth) { continue }
+            for (name, file) in &directory.file {
+                let node = Node::File(file);
+                if self.finder.accepts(name, depth + 1.0, &node) { self.buffer.push_back((join_path(&path, name), node)) }
+            }
+            for (name, subdirectory) in &directory.directory {
+                let node = Node::Dir(subdirectory);
+                if self.finder.accepts(name, depth + 1.0, &node) { self.buffer.push_back((join_path(&path, name), node)) }
+                self.stack.push((join_path(&path, name), subdirectory));
+            }
+        }
+    }
+}
+
+
+// #===========================#
+// #=== DIR IMPLEMENTATIONS ===#
+
+impl <T> DirMulti<T> {
+    /// Start building a filtered search over all subdirectories and files, see [`Finder`]
+    pub fn find(&self) -> Finder<'_, T> {
+        Finder::new(self)
+    }
+}
+impl <T> DirMapMulti<T> {
+    /// Start building a filtered search over all directories and files, see [`Finder`]
+    pub fn find(&self) -> Finder<'_, T> {
+        self.directory.find()
+    }
+}
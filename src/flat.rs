@@ -0,0 +1,76 @@
+use std::borrow::Borrow;
+use std::collections::BTreeMap;
+
+use crate::{DirError, DirHierarchy, DirMapMulti, DirMulti, DirectoryInit, PathTreeInit, TreePath};
+use crate::tree::{join_names, resolve_parent};
+
+// #============================#
+// #=== FLAT IMPLEMENTATIONS ===#
+
+impl <T> DirMulti<T> {
+    /// Consume the directory into a map of files keyed by their slash-joined paths relative to it.
+    /// Empty directories and links have no key, so they are dropped.
+    pub fn flatten(self) -> BTreeMap<String, T> {
+        self.into_iter().collect()
+    }
+
+    /// Build directory from files keyed by their paths, creating directories as needed. Accepts any map or iterator of pairs.
+    ///
+    /// Keys are resolved like any other path, so `a//b` and `a/./b` refer to the same file and produce [`DirError::NameInUse`].
+    /// A key that is also a directory prefix of another key fails with [`DirError::AmbiguousPath`].
+    /// ```
+    /// # use pathio::prelude::*;
+    /// # use std::collections::HashMap;
+    /// let flat = HashMap::from([("config/audio", 1), ("config/video", 2), ("save", 3)]);
+    /// let directory = Directory::unflatten(flat).unwrap();
+    /// assert_eq!(directory.borrow_file("config/video"), Ok(&2));
+    /// assert_eq!(directory.flatten().into_keys().collect::<Vec<_>>(), ["config/audio", "config/video", "save"]);
+    ///
+    /// let ambiguous = [("config", 0), ("config/audio", 1)];
+    /// assert_eq!(Directory::unflatten(ambiguous), Err(DirError::AmbiguousPath("config".to_owned())));
+    /// ```
+    pub fn unflatten<P: AsRef<TreePath>>(map: impl IntoIterator<Item = (P, T)>) -> Result<Self, DirError> {
+        let mut directory = DirMulti::new();
+        for (path, file) in map {
+            directory.insert_flat(path.as_ref(), file)?;
+        }
+        Ok(directory)
+    }
+
+    /// Insert file at the path, creating missing directories and rejecting names used both by a file and a directory
+    fn insert_flat(&mut self, path: &TreePath, file: T) -> Result<(), DirError> {
+        let (names, name) = resolve_parent(self.get_path(), path)?;
+        let directory = self.ensure_flat_dir_mut(&names)?;
+        let path = join_names(&names, name);
+        if directory.directory.contains_key(name) { return Err(DirError::AmbiguousPath(path)) }
+        if directory.file.contains_key(name) { return Err(DirError::NameInUse(path)) }
+        directory.file.insert(name.to_owned(), file);
+        Ok(())
+    }
+
+    /// Mutably borrow directory located at the resolved names, creating missing directories and rejecting names used by a file or link on the way
+    pub(crate) fn ensure_flat_dir_mut(&mut self, names: &[&str]) -> Result<&mut DirMulti<T>, DirError> {
+        let mut directory = self;
+        for (i, branch) in names.iter().enumerate() {
+            if directory.file.contains_key(*branch) || directory.link.contains_key(*branch) { return Err(DirError::AmbiguousPath(join_names(&names[..i], branch))) }
+            if !directory.directory.contains_key(*branch) { directory.add_dir(*branch, DirMulti::new())?; }
+            directory = directory.obtain_dir_mut(*branch)?;
+        }
+        Ok(directory)
+    }
+}
+impl <T> DirMapMulti<T> {
+    /// Consume the tree into a map of files keyed by their slash-joined paths, see [`DirMulti::flatten`]
+    pub fn flatten(self) -> BTreeMap<String, T> {
+        self.directory.flatten()
+    }
+
+    /// Build tree with the name from files keyed by their paths, see [`DirMulti::unflatten`]
+    pub fn unflatten<P: AsRef<TreePath>>(name: impl Borrow<str>, map: impl IntoIterator<Item = (P, T)>) -> Result<Self, DirError> {
+        let mut tree = DirMapMulti::new(name);
+        for (path, file) in map {
+            tree.directory.insert_flat(path.as_ref(), file)?;
+        }
+        Ok(tree)
+    }
+}
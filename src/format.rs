@@ -0,0 +1,213 @@
+use std::fmt;
+use colored::Colorize;
+
+use crate::{DirHierarchy, DirMapMulti, DirMapSingle, DirMulti, DirNode, DirSingle};
+
+// #==============================#
+// #=== FORMAT IMPLEMENTATIONS ===#
+
+/// ## Tree style
+/// Characters used to draw the branches of a rendered tree.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum TreeStyle {
+    /// Original `|->` arrows, every level indented the same
+    #[default]
+    Arrow,
+
+    /// ASCII branches like `tree --charset=ascii`, using `|--` and `` `-- ``
+    Ascii,
+
+    /// Unicode box-drawing branches like `tree`, using `├──` and `└──`
+    Unicode,
+}
+impl TreeStyle {
+    /// Text placed before the first entry of every line
+    fn line_start(&self) -> &'static str {
+        match self {
+            TreeStyle::Arrow => "\n  ",
+            _ => "\n",
+        }
+    }
+
+    /// Branch pointing at an entry
+    fn connector(&self, last: bool) -> &'static str {
+        match (self, last) {
+            (TreeStyle::Arrow, _) => "|-> ",
+            (TreeStyle::Ascii, false) => "|-- ",
+            (TreeStyle::Ascii, true) => "`-- ",
+            (TreeStyle::Unicode, false) => "├── ",
+            (TreeStyle::Unicode, true) => "└── ",
+        }
+    }
+
+    /// Indentation continuing below an entry into its subdirectory
+    fn indent(&self, last: bool) -> &'static str {
+        match (self, last) {
+            (TreeStyle::Arrow, _) => "|    ",
+            (TreeStyle::Ascii, false) => "|   ",
+            (TreeStyle::Unicode, false) => "│   ",
+            (_, true) => "    ",
+        }
+    }
+}
+
+/// Boxed callback labelling a file in [`TreeFormat`]
+type FileLabel<'a, F> = Box<dyn Fn(&F) -> String + 'a>;
+
+
+/// ## Tree format
+/// Options for rendering overview of a directory, the output is produced through [`Display`](fmt::Display).
+///
+/// By default the output is coloured, drawn with [`TreeStyle::Arrow`], sorted by name, has no depth limit,
+/// hides entries whose name starts with `.` and lists files before subdirectories.
+/// ```
+/// # use pathio::prelude::*;
+/// let mut tree: PathTree<u32> = PathTree::new("Root");
+/// tree.extend([("b/two", 2), ("a/one", 1), ("top", 0)]);
+/// let text = tree.tree_format()
+///     .color(false)
+///     .style(TreeStyle::Unicode)
+///     .label(|size| format!("({size} B)"))
+///     .to_string();
+/// assert_eq!(text, "Root\n├── top (0 B)\n├── a\n│   └── one (1 B)\n└── b\n    └── two (2 B)");
+/// ```
+pub struct TreeFormat<'a, D: DirNode> {
+    root: &'a D,
+    color: bool,
+    style: TreeStyle,
+    sorted: bool,
+    max_depth: Option<usize>,
+    hidden: bool,
+    files: bool,
+    label: Option<FileLabel<'a, D::File>>,
+}
+impl <'a, D: DirNode + DirHierarchy<D>> TreeFormat<'a, D> {
+    /// Create default format of the directory
+    pub fn new(root: &'a D) -> Self {
+        TreeFormat { root, color: true, style: TreeStyle::default(), sorted: true, max_depth: None, hidden: false, files: true, label: None }
+    }
+
+    /// Enable or disable ANSI colours
+    pub fn color(mut self, color: bool) -> Self {
+        self.color = color;
+        self
+    }
+
+    /// Set characters used to draw the branches
+    pub fn style(mut self, style: TreeStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Sort entries by name, otherwise they are listed in storage order
+    pub fn sorted(mut self, sorted: bool) -> Self {
+        self.sorted = sorted;
+        self
+    }
+
+    /// Only render the given number of levels below the root
+    pub fn max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = Some(max_depth);
+        self
+    }
+
+    /// Show entries whose name starts with `.`
+    pub fn hidden(mut self, hidden: bool) -> Self {
+        self.hidden = hidden;
+        self
+    }
+
+    /// Show files, otherwise only directories are rendered
+    pub fn files(mut self, files: bool) -> Self {
+        self.files = files;
+        self
+    }
+
+    /// Append label returned by the callback after the name of every file
+    pub fn label(mut self, label: impl Fn(&D::File) -> String + 'a) -> Self {
+        self.label = Some(Box::new(label));
+        self
+    }
+
+    /// Write single entry on a new line
+    fn write_entry(&self, f: &mut fmt::Formatter<'_>, prefix: &str, last: bool, name: &str, is_dir: bool) -> fmt::Result {
+        let branch = format!("{}{}{}", self.style.line_start(), prefix, self.style.connector(last));
+        match (self.color, is_dir) {
+            (false, _) => write!(f, "{branch}{name}"),
+            (true, true) => write!(f, "{}{}", branch.black(), name.bold().yellow()),
+            (true, false) => write!(f, "{}{}", branch.black(), name.bold().bright_cyan()),
+        }
+    }
+
+    /// Recursively write entries of the directory with the given branch prefix
+    fn cascade(&self, f: &mut fmt::Formatter<'_>, directory: &D, prefix: &mut String, level: usize) -> fmt::Result {
+        if self.max_depth.is_some_and(|max_depth| level >= max_depth) { return Ok(()) }
+        let visible = |name: &str| self.hidden || !name.starts_with('.');
+        let mut files: Vec<(Option<&String>, &D::File)> = match self.files {
+            true => directory.files().filter(|(name, _)| name.is_none_or(|name| visible(name))).collect(),
+            false => Vec::new(),
+        };
+        let mut directories: Vec<(&String, &D)> = directory.subdirs().filter(|(name, _)| visible(name)).collect();
+        if self.sorted {
+            files.sort_by_key(|(name, _)| *name);
+            directories.sort_by_key(|(name, _)| *name);
+        }
+        let count = files.len() + directories.len();
+        for (i, (name, file)) in files.iter().enumerate() {
+            let mut text = name.map_or("FILE", |name| name.as_str()).to_owned();
+            if let Some(label) = &self.label {
+                text = format!("{text} {}", label(file));
+            }
+            self.write_entry(f, prefix, i + 1 == count, &text, false)?;
+        }
+        for (i, (name, subdirectory)) in directories.iter().enumerate() {
+            let last = files.len() + i + 1 == count;
+            self.write_entry(f, prefix, last, name, true)?;
+            let length = prefix.len();
+            prefix.push_str(self.style.indent(last));
+            self.cascade(f, subdirectory, prefix, level + 1)?;
+            prefix.truncate(length);
+        }
+        Ok(())
+    }
+}
+impl <D: DirNode + DirHierarchy<D>> fmt::Display for TreeFormat<'_, D> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = self.root.get_name();
+        let marker = if self.style == TreeStyle::Arrow { "> " } else { "" };
+        match self.color {
+            true => write!(f, "{marker}{}", name.purple().bold().underline())?,
+            false => write!(f, "{marker}{name}")?,
+        }
+        self.cascade(f, self.root, &mut String::new(), 0)
+    }
+}
+
+
+// #===========================#
+// #=== DIR IMPLEMENTATIONS ===#
+
+impl <T> DirMulti<T> {
+    /// Start configuring overview of the tree, see [`TreeFormat`]
+    pub fn tree_format(&self) -> TreeFormat<'_, DirMulti<T>> {
+        TreeFormat::new(self)
+    }
+}
+impl <T> DirSingle<T> {
+    /// Start configuring overview of the tree, see [`TreeFormat`]
+    pub fn tree_format(&self) -> TreeFormat<'_, DirSingle<T>> {
+        TreeFormat::new(self)
+    }
+}
+impl <T> DirMapMulti<T> {
+    /// Start configuring overview of the tree, see [`TreeFormat`]
+    pub fn tree_format(&self) -> TreeFormat<'_, DirMulti<T>> {
+        self.directory.tree_format()
+    }
+}
+impl <T> DirMapSingle<T> {
+    /// Start configuring overview of the tree, see [`TreeFormat`]
+    pub fn tree_format(&self) -> TreeFormat<'_, DirSingle<T>> {
+        self.directory.tree_format()
+    }
+}
@@ -0,0 +1,464 @@
+use std::fs;
+use std::io;
+use std::path::{Component as FsComponent, Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::{DirError, DirFiles, DirHierarchy, DirMapMulti, DirMulti, DirectoryInit, Pattern, PathTreeInit, TreePathBuf};
+use crate::tree::{join_path, resolve_path};
+
+// #==============================#
+// #=== IMPORT IMPLEMENTATIONS ===#
+
+/// Convert I/O error into [`DirError::Io`] mentioning the path it happened at
+pub(crate) fn io_error(path: &Path, error: io::Error) -> DirError {
+    DirError::Io(format!("{}: {error}", path.display()))
+}
+
+/// Convert relative target of a symlink on disk into a tree path, absolute targets can't be represented
+pub(crate) fn link_target(target: &Path) -> Option<TreePathBuf> {
+    let mut names = Vec::new();
+    for component in target.components() {
+        match component {
+            FsComponent::CurDir => {},
+            FsComponent::ParentDir => names.push(".."),
+            FsComponent::Normal(name) => names.push(name.to_str()?),
+            FsComponent::RootDir | FsComponent::Prefix(_) => return None,
+        }
+    }
+    Some(TreePathBuf::new(names.join("/")))
+}
+
+
+/// ## Import options
+/// Options for mirroring a directory on disk into a tree with [`DirMapMulti::from_fs_with`].
+///
+/// By default symlinks are not followed, hidden entries are imported, there is no depth limit and nothing is ignored.
+/// Symlinks that are not followed become links of the tree if their target is relative, others are skipped.
+/// Entries are imported in the order of their names.
+/// ```no_run
+/// # use pathio::prelude::*;
+/// let options = ImportOptions::new()
+///     .hidden(false)
+///     .max_depth(4)
+///     .ignore(Pattern::new("**/*.tmp").unwrap());
+/// let assets: PathTree<Vec<u8>> = PathTree::from_fs_with("assets", &options, |path| std::fs::read(path)).unwrap();
+/// ```
+#[derive(Debug, Clone)]
+pub struct ImportOptions {
+    follow_links: bool,
+    hidden: bool,
+    max_depth: Option<usize>,
+    ignore: Vec<Pattern>,
+}
+impl ImportOptions {
+    /// Create default options
+    pub fn new() -> Self {
+        ImportOptions { follow_links: false, hidden: true, max_depth: None, ignore: Vec::new() }
+    }
+
+    /// Import the content symlinks point at instead of the symlinks themselves
+    pub fn follow_links(mut self, follow_links: bool) -> Self {
+        self.follow_links = follow_links;
+        self
+    }
+
+    /// Import entries whose name starts with `.`
+    pub fn hidden(mut self, hidden: bool) -> Self {
+        self.hidden = hidden;
+        self
+    }
+
+    /// Only import the given number of levels below the root, directories at the limit are imported empty
+    pub fn max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = Some(max_depth);
+        self
+    }
+
+    /// Skip entries whose path relative to the root matches the pattern, ignored directories are not read at all
+    pub fn ignore(mut self, pattern: Pattern) -> Self {
+        self.ignore.push(pattern);
+        self
+    }
+
+    /// Recursively mirror the directory on disk into the tree directory
+    fn import<T>(&self, directory: &mut DirMulti<T>, source: &Path, relative: &str, level: usize, ancestors: &mut Vec<PathBuf>, loader: &mut impl FnMut(&Path) -> io::Result<T>) -> Result<(), DirError> {
+        if self.max_depth.is_some_and(|max_depth| level >= max_depth) { return Ok(()) }
+        let mut entries = match fs::read_dir(source) {
+            Ok(entries) => entries.collect::<io::Result<Vec<_>>>().map_err(|e| io_error(source, e))?,
+            Err(e) => return Err(io_error(source, e)),
+        };
+        entries.sort_by_key(|entry| entry.file_name());
+        for entry in entries {
+            let path = entry.path();
+            let name = match entry.file_name().into_string() {
+                Ok(name) => name,
+                Err(name) => return Err(DirError::InvalidPath(name.to_string_lossy().into_owned())),
+            };
+            let tree_path = join_path(relative, &name);
+            if !self.hidden && name.starts_with('.') { continue }
+            if self.ignore.iter().any(|pattern| pattern.matches(&tree_path)) { continue }
+            let mut file_type = entry.file_type().map_err(|e| io_error(&path, e))?;
+            if file_type.is_symlink() {
+                if !self.follow_links {
+                    let target = fs::read_link(&path).map_err(|e| io_error(&path, e))?;
+                    if let Some(target) = link_target(&target) { directory.link.insert(name, target); }
+                    continue;
+                }
+                file_type = fs::metadata(&path).map_err(|e| io_error(&path, e))?.file_type();
+            }
+            if file_type.is_dir() {
+                let canonical = fs::canonicalize(&path).map_err(|e| io_error(&path, e))?;
+                if ancestors.contains(&canonical) { return Err(DirError::LinkLoop(tree_path)) }
+                directory.add_dir(name.as_str(), DirMulti::new())?;
+                ancestors.push(canonical);
+                self.import(directory.obtain_dir_mut(name)?, &path, &tree_path, level + 1, ancestors, loader)?;
+                ancestors.pop();
+            } else {
+                let file = loader(&path).map_err(|e| io_error(&path, e))?;
+                directory.add_file(name, file)?;
+            }
+        }
+        Ok(())
+    }
+}
+impl Default for ImportOptions {
+    fn default() -> Self {
+        ImportOptions::new()
+    }
+}
+
+
+// #==============================#
+// #=== EXPORT IMPLEMENTATIONS ===#
+
+/// ## Export mode
+/// How [`DirMulti::write_to_fs_with`] treats content already present in the target directory.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ExportMode {
+    /// Add the tree to the target, existing entries in the way are an error
+    #[default]
+    Create,
+
+    /// Add the tree to the target, replacing existing entries in the way
+    Overwrite,
+
+    /// Replace the whole target, so it contains only the tree
+    Clean,
+}
+
+
+/// ## Export options
+/// Options for writing a tree to disk with [`DirMulti::write_to_fs_with`].
+///
+/// By default the tree is written in [`ExportMode::Create`] and the run is not dry.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ExportOptions {
+    mode: ExportMode,
+    dry_run: bool,
+}
+impl ExportOptions {
+    /// Create default options
+    pub fn new() -> Self {
+        ExportOptions::default()
+    }
+
+    /// Set how existing content of the target is treated
+    pub fn mode(mut self, mode: ExportMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Only compute the report without touching the disk, conflicts are still reported as errors
+    pub fn dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+}
+
+
+/// ## Export report
+/// Summary of a tree written to disk, paths are relative to the target directory and listed parents first.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ExportReport {
+    /// Directories that didn't exist before
+    pub created_dirs: Vec<String>,
+
+    /// Files written to paths that were free
+    pub written: Vec<String>,
+
+    /// Symlinks created for links of the tree at paths that were free
+    pub linked: Vec<String>,
+
+    /// Existing entries replaced by an entry of the tree
+    pub overwritten: Vec<String>,
+
+    /// Existing entries removed because they are not in the tree, only in [`ExportMode::Clean`]
+    pub removed: Vec<String>,
+
+    /// Links of the tree that were not written, absolute links pointing outside of the directory and every link on targets without symlinks
+    pub skipped_links: Vec<String>,
+
+    /// True if nothing was actually written
+    pub dry_run: bool,
+}
+
+
+/// Entry of the tree waiting to be written
+pub(crate) enum Planned<'a, T> {
+    /// Directory to create
+    Dir,
+    /// File to turn into bytes
+    File (&'a T),
+    /// Symlink with the target converted for the disk
+    Link (String),
+}
+
+/// Recursively list entries of the directory parents first, `level` is the number of directories between the exported root and this one.
+/// Links are listed only if `links` is set, absolute targets are rewritten relative to the link. Links that can't be listed are recorded in `skipped`.
+pub(crate) fn plan<'a, T>(directory: &'a DirMulti<T>, base: &str, relative: &str, level: usize, links: bool, planned: &mut Vec<(String, Planned<'a, T>)>, skipped: &mut Vec<String>) {
+    let mut files: Vec<_> = directory.file.iter().collect();
+    files.sort_by_key(|(name, _)| *name);
+    for (name, file) in files {
+        planned.push((join_path(relative, name), Planned::File(file)));
+    }
+    let mut sorted_links: Vec<_> = directory.link.iter().collect();
+    sorted_links.sort_by_key(|(name, _)| *name);
+    for (name, target) in sorted_links {
+        let target = match (links, target.is_absolute()) {
+            (false, _) => None,
+            (true, false) => Some(target.to_string()),
+            (true, true) => resolve_path(base, target).ok().map(|names| "../".repeat(level) + &names.join("/")),
+        };
+        match target {
+            Some(target) => planned.push((join_path(relative, name), Planned::Link(target))),
+            None => skipped.push(join_path(relative, name)),
+        }
+    }
+    let mut directories: Vec<_> = directory.directory.iter().collect();
+    directories.sort_by_key(|(name, _)| *name);
+    for (name, subdirectory) in directories {
+        let path = join_path(relative, name);
+        planned.push((path.to_owned(), Planned::Dir));
+        plan(subdirectory, base, &path, level + 1, links, planned, skipped);
+    }
+}
+
+/// Returns whether something exists at the path without following symlinks and if it is a real directory
+fn disk_kind(path: &Path) -> Option<bool> {
+    fs::symlink_metadata(path).ok().map(|metadata| metadata.is_dir())
+}
+
+/// Remove file, symlink or whole directory at the path
+fn remove_entry(path: &Path) -> io::Result<()> {
+    match disk_kind(path) {
+        Some(true) => fs::remove_dir_all(path),
+        Some(false) => fs::remove_file(path),
+        None => Ok(()),
+    }
+}
+
+/// Recursively collect existing entries of the target that are not in the tree, removed subtrees are listed only once
+fn collect_removed(source: &Path, relative: &str, kept: &[&str], removed: &mut Vec<String>) -> Result<(), DirError> {
+    let mut entries = fs::read_dir(source).and_then(|entries| entries.collect::<io::Result<Vec<_>>>()).map_err(|e| io_error(source, e))?;
+    entries.sort_by_key(|entry| entry.file_name());
+    for entry in entries {
+        let path = join_path(relative, &entry.file_name().to_string_lossy());
+        match kept.binary_search(&path.as_str()) {
+            Err(_) => removed.push(path),
+            Ok(_) => if disk_kind(&entry.path()) == Some(true) { collect_removed(&entry.path(), &path, kept, removed)? },
+        }
+    }
+    Ok(())
+}
+
+/// Write planned entries into a fresh directory
+fn stage<T>(staging: &Path, planned: &[(String, Planned<'_, T>)], writer: &impl Fn(&T) -> io::Result<Vec<u8>>) -> Result<(), DirError> {
+    fs::create_dir(staging).map_err(|e| io_error(staging, e))?;
+    for (path, entry) in planned {
+        let target = staging.join(path);
+        let result = match entry {
+            Planned::Dir => fs::create_dir(&target),
+            Planned::File(file) => writer(file).and_then(|bytes| fs::write(&target, bytes)),
+            #[cfg(unix)]
+            Planned::Link(link) => std::os::unix::fs::symlink(link, &target),
+            #[cfg(not(unix))]
+            Planned::Link(_) => Ok(()),
+        };
+        result.map_err(|e| io_error(&target, e))?;
+    }
+    Ok(())
+}
+
+/// Sibling of the target used while writing it, unique for every call in the process
+fn sibling(root: &Path, name: &str, purpose: &str) -> PathBuf {
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+    root.with_file_name(format!(".{name}.{purpose}-{}-{}", std::process::id(), COUNTER.fetch_add(1, Ordering::Relaxed)))
+}
+
+/// Step of [`commit`] that can be undone
+enum Committed<'a> {
+    /// Entry was created in the target
+    Created (&'a str),
+
+    /// Entry in the way was moved into the backup
+    Replaced (&'a str),
+}
+
+/// Move staged entries into an existing target one by one. Entries in the way are moved into the backup,
+/// if any step fails everything done so far is undone in reverse order.
+fn commit<T>(staging: &Path, backup: &Path, root: &Path, planned: &[(String, Planned<'_, T>)]) -> io::Result<()> {
+    let mut done = Vec::new();
+    if let Err(e) = commit_entries(staging, backup, root, planned, &mut done) {
+        for step in done.into_iter().rev() {
+            let _ = match step {
+                Committed::Created(path) => remove_entry(&root.join(path)),
+                Committed::Replaced(path) => fs::rename(backup.join(path), root.join(path)),
+            };
+        }
+        let _ = fs::remove_dir_all(backup);
+        return Err(e);
+    }
+    remove_entry(backup)?;
+    fs::remove_dir_all(staging)
+}
+
+/// Move staged entries into the target, recording every step taken
+fn commit_entries<'a, T>(staging: &Path, backup: &Path, root: &Path, planned: &'a [(String, Planned<'_, T>)], done: &mut Vec<Committed<'a>>) -> io::Result<()> {
+    for (path, entry) in planned {
+        let target = root.join(path);
+        let on_disk = disk_kind(&target);
+        if matches!((entry, on_disk), (Planned::Dir, Some(true))) { continue }
+        if on_disk.is_some() {
+            let aside = backup.join(path);
+            if let Some(parent) = aside.parent() { fs::create_dir_all(parent)?; }
+            fs::rename(&target, &aside)?;
+            done.push(Committed::Replaced(path));
+        }
+        match entry {
+            Planned::Dir => fs::create_dir(&target)?,
+            _ => fs::rename(staging.join(path), &target)?,
+        }
+        done.push(Committed::Created(path));
+    }
+    Ok(())
+}
+
+// #===========================#
+// #=== DIR IMPLEMENTATIONS ===#
+
+impl <T> DirMapMulti<T> {
+    /// Mirror directory on disk into a new tree named after it, the loader turns every file into `<T>`. See [`ImportOptions`] for the defaults.
+    pub fn from_fs(root: impl AsRef<Path>, loader: impl FnMut(&Path) -> io::Result<T>) -> Result<Self, DirError> {
+        DirMapMulti::from_fs_with(root, &ImportOptions::new(), loader)
+    }
+
+    /// Mirror directory on disk into a new tree named after it using the options, the loader turns every file into `<T>`
+    pub fn from_fs_with(root: impl AsRef<Path>, options: &ImportOptions, mut loader: impl FnMut(&Path) -> io::Result<T>) -> Result<Self, DirError> {
+        let root = root.as_ref();
+        let canonical = fs::canonicalize(root).map_err(|e| io_error(root, e))?;
+        let name = match canonical.file_name() {
+            Some(name) => name.to_string_lossy().into_owned(),
+            None => canonical.display().to_string(),
+        };
+        let mut tree = DirMapMulti::new(name);
+        options.import(&mut tree.directory, root, "", 0, &mut vec![canonical], &mut loader)?;
+        Ok(tree)
+    }
+}
+impl <T> DirMulti<T> {
+    /// Write the directory to disk, creating a directory for every subdirectory and a file for every file turned into bytes by the writer.
+    /// See [`ExportOptions`] for the defaults.
+    pub fn write_to_fs(&self, root: impl AsRef<Path>, writer: impl Fn(&T) -> io::Result<Vec<u8>>) -> Result<ExportReport, DirError> {
+        self.write_to_fs_with(root, &ExportOptions::new(), writer)
+    }
+
+    /// Write the directory to disk using the options, returns report of what was written.
+    ///
+    /// Conflicts are detected before anything is written. The content is then staged into a temporary directory next to the target,
+    /// which is renamed into place when the target doesn't exist or is replaced by [`ExportMode::Clean`]. Otherwise the staged entries are
+    /// renamed into the existing target one by one and the entries in their way are moved aside, so a failure restores the target as it was.
+    /// Links of the tree become symlinks on Unix, absolute links pointing outside of the directory are skipped and listed in the report.
+    /// ```
+    /// # use pathio::prelude::*;
+    /// let mut scaffold: PathTree<&str> = PathTree::new("project");
+    /// scaffold.insert_file_with_parents("src/main.rs", "fn main() {}").unwrap();
+    /// let target = std::env::temp_dir().join(format!("pathio-doc-export-{}", std::process::id()));
+    ///
+    /// let report = scaffold.write_to_fs_with(&target, &ExportOptions::new().dry_run(true), |text| Ok(text.as_bytes().to_vec())).unwrap();
+    /// assert_eq!(report.written, ["src/main.rs"]);
+    /// assert!(!target.exists());
+    /// ```
+    pub fn write_to_fs_with(&self, root: impl AsRef<Path>, options: &ExportOptions, writer: impl Fn(&T) -> io::Result<Vec<u8>>) -> Result<ExportReport, DirError> {
+        let root = root.as_ref();
+        let name = match root.file_name() {
+            Some(name) => name.to_string_lossy().into_owned(),
+            None => return Err(DirError::InvalidPath(root.display().to_string())),
+        };
+        let (mut planned, mut skipped_links) = (Vec::new(), Vec::new());
+        plan(self, self.get_path(), "", 0, cfg!(unix), &mut planned, &mut skipped_links);
+
+        let mut report = ExportReport { dry_run: options.dry_run, skipped_links, ..Default::default() };
+        let existing = disk_kind(root);
+        if existing == Some(false) && options.mode != ExportMode::Clean { return Err(DirError::NameInUse(root.display().to_string())) }
+        for (path, entry) in &planned {
+            let on_disk = if existing == Some(true) { disk_kind(&root.join(path)) } else { None };
+            match (entry, on_disk) {
+                (Planned::Dir, Some(true)) => {},
+                (Planned::Dir, None) => report.created_dirs.push(path.to_owned()),
+                (Planned::File(_), None) => report.written.push(path.to_owned()),
+                (Planned::Link(_), None) => report.linked.push(path.to_owned()),
+                (_, Some(_)) => match options.mode {
+                    ExportMode::Create => return Err(DirError::NameInUse(path.to_owned())),
+                    _ => report.overwritten.push(path.to_owned()),
+                },
+            }
+        }
+        if existing == Some(true) && options.mode == ExportMode::Clean {
+            let mut kept: Vec<&str> = planned.iter().filter(|(_, entry)| matches!(entry, Planned::Dir)).map(|(path, _)| path.as_str()).collect();
+            kept.sort_unstable();
+            collect_removed(root, "", &kept, &mut report.removed)?;
+            let replaced: Vec<&str> = planned.iter().filter(|(_, entry)| !matches!(entry, Planned::Dir)).map(|(path, _)| path.as_str()).collect();
+            report.removed.retain(|path| !replaced.contains(&path.as_str()));
+        }
+        if options.dry_run { return Ok(report) }
+
+        if let Some(parent) = root.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+            fs::create_dir_all(parent).map_err(|e| io_error(parent, e))?;
+        }
+        let staging = sibling(root, &name, "staging");
+        remove_entry(&staging).map_err(|e| io_error(&staging, e))?;
+        if let Err(e) = stage(&staging, &planned, &writer) {
+            let _ = fs::remove_dir_all(&staging);
+            return Err(e);
+        }
+        let backup = sibling(root, &name, "backup");
+        let result = match (existing, options.mode) {
+            (None, _) => fs::rename(&staging, root),
+            (Some(_), ExportMode::Clean) => {
+                fs::rename(root, &backup).and_then(|_| match fs::rename(&staging, root) {
+                    Ok(_) => remove_entry(&backup),
+                    Err(e) => fs::rename(&backup, root).and(Err(e)),
+                })
+            },
+            (Some(_), _) => commit(&staging, &backup, root, &planned),
+        };
+        match result {
+            Ok(_) => Ok(report),
+            Err(e) => {
+                let _ = fs::remove_dir_all(&staging);
+                Err(io_error(root, e))
+            },
+        }
+    }
+}
+impl <T> DirMapMulti<T> {
+    /// Write the tree to disk, see [`DirMulti::write_to_fs`]
+    pub fn write_to_fs(&self, root: impl AsRef<Path>, writer: impl Fn(&T) -> io::Result<Vec<u8>>) -> Result<ExportReport, DirError> {
+        self.directory.write_to_fs(root, writer)
+    }
+
+    /// Write the tree to disk using the options, see [`DirMulti::write_to_fs_with`]
+    pub fn write_to_fs_with(&self, root: impl AsRef<Path>, options: &ExportOptions, writer: impl Fn(&T) -> io::Result<Vec<u8>>) -> Result<ExportReport, DirError> {
+        self.directory.write_to_fs_with(root, options, writer)
+    }
+}
@@ -0,0 +1,307 @@
+use std::collections::HashSet;
+use std::iter::Peekable;
+use std::str::Chars;
+
+use crate::{Component, DirError, DirMapMulti, DirMulti, TreePath};
+use crate::tree::join_path;
+
+// #============================#
+// #=== GLOB IMPLEMENTATIONS ===#
+
+/// ## Node
+/// Directory or file found in the tree.
+#[derive(Debug, PartialEq)]
+pub enum Node<'a, T> {
+    /// Matched directory
+    Dir (&'a DirMulti<T>),
+
+    /// Matched file
+    File (&'a T),
+}
+impl <T> Node<'_, T> {
+    /// Returns true if the node is a directory
+    pub fn is_dir(&self) -> bool {
+        matches!(self, Node::Dir(_))
+    }
+
+    /// Returns true if the node is a file
+    pub fn is_file(&self) -> bool {
+        matches!(self, Node::File(_))
+    }
+}
+impl <T> Clone for Node<'_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+impl <T> Copy for Node<'_, T> {}
+
+
+/// Single matching unit of a name
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    /// Exact character
+    Literal (char),
+    /// `?`, any single character
+    One,
+    /// `*`, any sequence of characters
+    Any,
+    /// `[...]`, character from the ranges, or not from them if negated
+    Class { negated: bool, ranges: Vec<(char, char)> },
+}
+
+/// Part of the pattern between two `/`
+#[derive(Debug, Clone, PartialEq)]
+enum Segment {
+    /// Name without any special characters, looked up directly
+    Literal (String),
+    /// Name with wildcards, every `{a,b}` alternative is expanded into its own token sequence
+    Wildcard (Vec<Vec<Token>>),
+    /// `**`, any number of directories
+    Recursive,
+}
+impl Segment {
+    /// Check if the name of a single directory or file matches this segment
+    fn matches(&self, name: &str) -> bool {
+        match self {
+            Segment::Literal(literal) => literal == name,
+            Segment::Wildcard(alternatives) => {
+                let name: Vec<char> = name.chars().collect();
+                alternatives.iter().any(|tokens| match_tokens(tokens, &name))
+            },
+            Segment::Recursive => true,
+        }
+    }
+}
+
+/// Check if the characters match the token sequence
+fn match_tokens(tokens: &[Token], name: &[char]) -> bool {
+    match tokens.split_first() {
+        None => name.is_empty(),
+        Some((Token::Any, rest)) => (0..=name.len()).any(|i| match_tokens(rest, &name[i..])),
+        Some((token, rest)) => match name.split_first() {
+            Some((c, name)) => {
+                let matched = match token {
+                    Token::Literal(literal) => literal == c,
+                    Token::Class { negated, ranges } => ranges.iter().any(|(start, end)| start <= c && c <= end) != *negated,
+                    _ => true,
+                };
+                matched && match_tokens(rest, name)
+            },
+            None => false,
+        },
+    }
+}
+
+/// Check if the names of a path match the segment sequence. Every segment is matched against every name at most once,
+/// so the work is bounded by the number of segments times the number of names, no matter how many `**` there are.
+fn match_segments(segments: &[Segment], names: &[&str]) -> bool {
+    let mut reachable = vec![false; names.len() + 1];
+    reachable[0] = true;
+    for segment in segments {
+        let mut next = vec![false; names.len() + 1];
+        match segment {
+            Segment::Recursive => {
+                let mut any = false;
+                for (i, reached) in reachable.iter().enumerate() {
+                    any |= reached;
+                    next[i] = any;
+                }
+            },
+            _ => for (i, name) in names.iter().enumerate() {
+                next[i + 1] = reachable[i] && segment.matches(name);
+            },
+        }
+        reachable = next;
+    }
+    reachable[names.len()]
+}
+
+
+/// ## Pattern
+/// Compiled glob pattern matched against paths inside of a directory tree.
+///
+/// Supported syntax:
+/// * `*` matches any sequence of characters within a name, `?` matches any single character
+/// * `[abc]`, `[a-z]` match one character from the set, `[!abc]` or `[^abc]` one character outside of it
+/// * `{a,b}` matches any of the comma separated alternatives, which can't contain `/`
+/// * `**` as a whole segment matches any number of directories, including none
+/// * `\` escapes the next character
+///
+/// Patterns are relative to the directory they are matched in, empty and `.` segments are ignored.
+/// ```
+/// # use pathio::prelude::*;
+/// let pattern = Pattern::new("textures/**/*.{png,jpg}").unwrap();
+/// assert!(pattern.matches("textures/ui/button.png"));
+/// assert!(pattern.matches("textures/logo.jpg"));
+/// assert!(!pattern.matches("models/logo.png"));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Pattern {
+    pattern: String,
+    segments: Vec<Segment>,
+}
+impl Pattern {
+    /// Compile the pattern
+    pub fn new(pattern: impl AsRef<str>) -> Result<Self, DirError> {
+        let pattern = pattern.as_ref();
+        let mut segments = Vec::new();
+        for segment in pattern.split('/') {
+            let segment = match segment {
+                "" | "." => continue,
+                ".." => return Err(DirError::InvalidPattern(pattern.to_owned())),
+                "**" => {
+                    if segments.last() == Some(&Segment::Recursive) { continue }
+                    Segment::Recursive
+                },
+                _ if !segment.contains(['*', '?', '[', '{', '\\']) => Segment::Literal(segment.to_owned()),
+                _ => {
+                    let mut chars = segment.chars().peekable();
+                    let alternatives = parse_sequence(&mut chars, false, pattern)?;
+                    Segment::Wildcard(alternatives)
+                },
+            };
+            segments.push(segment);
+        }
+        Ok(Pattern { pattern: pattern.to_owned(), segments })
+    }
+
+    /// Returns the pattern as it was written
+    pub fn as_str(&self) -> &str {
+        &self.pattern
+    }
+
+    /// Check if the relative path matches the pattern
+    pub fn matches(&self, path: impl AsRef<TreePath>) -> bool {
+        let mut names = Vec::new();
+        for component in path.as_ref().components() {
+            match component {
+                Component::Normal(name) => names.push(name),
+                Component::RootDir => {},
+                Component::ParentDir => return false,
+            }
+        }
+        match_segments(&self.segments, &names)
+    }
+
+    /// Find all directories and files in the directory matching the pattern, yielding `(path, node)` sorted by path
+    ///
+    /// Only subdirectories that can still lead to a match are visited.
+    pub fn glob<'a, T>(&self, directory: &'a DirMulti<T>) -> Vec<(String, Node<'a, T>)> {
+        let mut found = Vec::new();
+        search(directory, String::new(), &self.segments, &mut HashSet::new(), &mut found);
+        found.sort_by(|(a, a_node), (b, b_node)| a.cmp(b).then(a_node.is_file().cmp(&b_node.is_file())));
+        found.dedup_by(|(a, a_node), (b, b_node)| a == b && a_node.is_file() == b_node.is_file());
+        found
+    }
+}
+
+/// Parse tokens of a segment until the end or until `,` or `}` of the enclosing braces, expanding braces into all alternatives
+fn parse_sequence(chars: &mut Peekable<Chars>, nested: bool, pattern: &str) -> Result<Vec<Vec<Token>>, DirError> {
+    let invalid = || DirError::InvalidPattern(pattern.to_owned());
+    let mut sequences = vec![Vec::new()];
+    while let Some(&c) = chars.peek() {
+        if nested && (c == ',' || c == '}') { break }
+        chars.next();
+        let token = match c {
+            '*' => Token::Any,
+            '?' => Token::One,
+            '\\' => Token::Literal(chars.next().ok_or_else(invalid)?),
+            '[' => {
+                let negated = matches!(chars.peek(), Some('!') | Some('^'));
+                if negated { chars.next(); }
+                let mut ranges = Vec::new();
+                let mut first = true;
+                loop {
+                    let start = match chars.next() {
+                        Some(']') if !first => break,
+                        Some('\\') => chars.next().ok_or_else(invalid)?,
+                        Some(c) => c,
+                        None => return Err(invalid()),
+                    };
+                    first = false;
+                    let mut lookahead = chars.clone();
+                    if lookahead.next() == Some('-') && lookahead.peek().is_some_and(|c| *c != ']') {
+                        chars.next();
+                        let end = chars.next().ok_or_else(invalid)?;
+                        ranges.push((start, end));
+                    } else {
+                        ranges.push((start, start));
+                    }
+                }
+                Token::Class { negated, ranges }
+            },
+            '{' => {
+                let mut alternatives = Vec::new();
+                loop {
+                    alternatives.extend(parse_sequence(chars, true, pattern)?);
+                    match chars.next() {
+                        Some(',') => continue,
+                        Some('}') => break,
+                        _ => return Err(invalid()),
+                    }
+                }
+                sequences = sequences.iter().flat_map(|sequence| alternatives.iter().map(move |alternative| [sequence.as_slice(), alternative].concat())).collect();
+                continue;
+            },
+            c => Token::Literal(c),
+        };
+        for sequence in &mut sequences { sequence.push(token.clone()) }
+    }
+    Ok(sequences)
+}
+
+/// Recursively collect nodes matching the remaining segments, descending only into matching subdirectories.
+/// Every directory is searched with the same remaining segments at most once, `visited` holds the pairs already searched.
+fn search<'a, T>(directory: &'a DirMulti<T>, path: String, segments: &[Segment], visited: &mut HashSet<(*const DirMulti<T>, usize)>, found: &mut Vec<(String, Node<'a, T>)>) {
+    if !visited.insert((directory as *const DirMulti<T>, segments.len())) { return }
+    match segments.split_first() {
+        None => if !path.is_empty() { found.push((path, Node::Dir(directory))) },
+        Some((Segment::Recursive, rest)) => {
+            if rest.is_empty() {
+                for (name, file) in &directory.file {
+                    found.push((join_path(&path, name), Node::File(file)));
+                }
+            }
+            for (name, subdirectory) in &directory.directory {
+                search(subdirectory, join_path(&path, name), segments, visited, found);
+            }
+            search(directory, path, rest, visited, found);
+        },
+        Some((segment, rest)) => {
+            if rest.is_empty() {
+                for (name, file) in &directory.file {
+                    if segment.matches(name) { found.push((join_path(&path, name), Node::File(file))) }
+                }
+            }
+            match segment {
+                Segment::Literal(name) => if let Some(subdirectory) = directory.directory.get(name) {
+                    search(subdirectory, join_path(&path, name), rest, visited, found);
+                },
+                _ => for (name, subdirectory) in &directory.directory {
+                    if segment.matches(name) { search(subdirectory, join_path(&path, name), rest, visited, found) }
+                },
+            }
+        },
+    }
+}
+
+
+// #===========================#
+// #=== DIR IMPLEMENTATIONS ===#
+
+impl <T> DirMulti<T> {
+    /// Find all directories and files matching the glob pattern, yielding `(path, node)` sorted by path. See [`Pattern`] for the syntax.
+    pub fn glob(&self, pattern: impl AsRef<str>) -> Result<Vec<(String, Node<'_, T>)>, DirError> {
+        match Pattern::new(pattern) {
+            Ok(pattern) => Ok(pattern.glob(self)),
+            Err(e) => Err(e),
+        }
+    }
+}
+impl <T> DirMapMulti<T> {
+    /// Find all directories and files matching the glob pattern, yielding `(path, node)` sorted by path. See [`Pattern`] for the syntax.
+    pub fn glob(&self, pattern: impl AsRef<str>) -> Result<Vec<(String, Node<'_, T>)>, DirError> {
+        self.directory.glob(pattern)
+    }
+}
@@ -0,0 +1,438 @@
+use std::collections::VecDeque;
+use crate::{PathTreeInit, DirError, DirHierarchy, TreePath, DirMapMulti, DirMapSingle, DirMulti, DirSingle};
+use crate::tree::{join_names, join_path, resolve_parent};
+
+
+// #===============================#
+// #=== GENERIC IMPLEMENTATIONS ===#
+
+/// ## Traversal
+/// Order in which the tree iterators visit directories.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Traversal {
+    /// Depth-first, directory is visited before its subdirectories
+    #[default]
+    DepthFirstPre,
+
+    /// Depth-first, directory is visited after all of its subdirectories
+    DepthFirstPost,
+
+    /// Breadth-first, directories are visited level by level
+    BreadthFirst,
+}
+
+/// ## Directory node
+/// Read access to the nested content of a directory, shared by the tree iterators.
+pub trait DirNode {
+    /// Type of the stored file
+    type File;
+
+    /// Iterate over direct subdirectories
+    fn subdirs(&self) -> impl Iterator<Item = (&String, &Self)>;
+
+    /// Iterate over files stored directly in this directory, unnamed file yields no name
+    fn files(&self) -> impl Iterator<Item = (Option<&String>, &Self::File)>;
+}
+
+
+// #======================#
+// #=== ITERATOR TYPES ===#
+
+/// # Walk
+/// Lazy iterator over a directory and all of its subdirectories.
+///
+/// Yields `(path, &directory)` pairs. The path is relative to the directory the walk started from, which is yielded with an empty path.
+pub struct Walk<'a, D> {
+    root: &'a D,
+    order: Traversal,
+    queue: VecDeque<(String, &'a D, usize, bool)>,
+}
+impl <'a, D: DirNode> Walk<'a, D> {
+    pub(crate) fn new(root: &'a D) -> Self {
+        let mut walk = Walk { root, order: Traversal::default(), queue: VecDeque::new() };
+        walk.queue.push_back((String::new(), root, 0, false));
+        walk
+    }
+
+    /// Restarts the walk with the given traversal order
+    pub fn order(mut self, order: Traversal) -> Self {
+        self.order = order;
+        self.queue.clear();
+        self.queue.push_back((String::new(), self.root, 0, false));
+        self
+    }
+
+    /// Yield next directory together with its depth relative to the walk root
+    pub(crate) fn next_with_depth(&mut self) -> Option<(String, &'a D, usize)> {
+        match self.order {
+            Traversal::DepthFirstPre => {
+                let (path, directory, depth, _) = self.queue.pop_back()?;
+                for (name, subdirectory) in directory.subdirs().collect::<Vec<_>>().into_iter().rev() {
+                    self.queue.push_back((join_path(&path, name), subdirectory, depth + 1, false));
+                }
+                Some((path, directory, depth))
+            },
+            Traversal::BreadthFirst => {
+                let (path, directory, depth, _) = self.queue.pop_front()?;
+                for (name, subdirectory) in directory.subdirs() {
+                    self.queue.push_back((join_path(&path, name), subdirectory, depth + 1, false));
+                }
+                Some((path, directory, depth))
+            },
+            Traversal::DepthFirstPost => loop {
+                let (path, directory, depth, expanded) = self.queue.back_mut()?;
+                if *expanded {
+                    let (path, directory, depth, _) = self.queue.pop_back()?;
+                    return Some((path, directory, depth));
+                }
+                *expanded = true;
+                let (path, directory, depth) = (path.to_owned(), *directory, *depth);
+                for (name, subdirectory) in directory.subdirs().collect::<Vec<_>>().into_iter().rev() {
+                    self.queue.push_back((join_path(&path, name), subdirectory, depth + 1, false));
+                }
+            },
+        }
+    }
+}
+impl <'a, D: DirNode> Iterator for Walk<'a, D> {
+    type Item = (String, &'a D);
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_with_depth().map(|(path, directory, _)| (path, directory))
+    }
+}
+
+
+/// # Iter Dirs
+/// Lazy iterator over all subdirectories, the directory it was created from is not included.
+///
+/// Yields `(path, &directory)` pairs with paths relative to the starting directory.
+pub struct IterDirs<'a, D> {
+    walk: Walk<'a, D>,
+}
+impl <'a, D: DirNode> IterDirs<'a, D> {
+    /// Restarts the iterator with the given traversal order
+    pub fn order(self, order: Traversal) -> Self {
+        IterDirs { walk: self.walk.order(order) }
+    }
+}
+impl <'a, D: DirNode> Iterator for IterDirs<'a, D> {
+    type Item = (String, &'a D);
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let (path, directory, depth) = self.walk.next_with_depth()?;
+            if depth != 0 { return Some((path, directory)) }
+        }
+    }
+}
+
+
+/// # Iter Files
+/// Lazy iterator over all files in a directory and its subdirectories.
+///
+/// Yields `(path, &file)` pairs with paths relative to the starting directory. Files are yielded in the order their directories are visited.
+pub struct IterFiles<'a, D: DirNode> {
+    walk: Walk<'a, D>,
+    buffer: VecDeque<(String, &'a D::File)>,
+}
+impl <'a, D: DirNode> IterFiles<'a, D> {
+    /// Restarts the iterator with the given traversal order
+    pub fn order(self, order: Traversal) -> Self {
+        IterFiles { walk: self.walk.order(order), buffer: VecDeque::new() }
+    }
+}
+impl <'a, D: DirNode> Iterator for IterFiles<'a, D> {
+    type Item = (String, &'a D::File);
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(item) = self.buffer.pop_front() { return Some(item) }
+            let (path, directory) = self.walk.next()?;
+            for (name, file) in directory.files() {
+                match name {
+                    Some(name) => self.buffer.push_back((join_path(&path, name), file)),
+                    None => self.buffer.push_back((path.to_owned(), file)),
+                }
+            }
+        }
+    }
+}
+
+
+/// # Iter Files Mut
+/// Lazy iterator over all files in a [`DirMulti`] and its subdirectories, giving mutable access.
+///
+/// Yields `(path, &mut file)` pairs with paths relative to the starting directory. Directories are visited depth-first.
+pub struct IterFilesMut<'a, T> {
+    stack: Vec<(String, &'a mut DirMulti<T>)>,
+    buffer: VecDeque<(String, &'a mut T)>,
+}
+impl <'a, T> Iterator for IterFilesMut<'a, T> {
+    type Item = (String, &'a mut T);
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(item) = self.buffer.pop_front() { return Some(item) }
+            let (path, directory) = self.stack.pop()?;
+            let DirMulti { file, directory, .. } = directory;
+            for (name, file) in file.iter_mut() {
+                self.buffer.push_back((join_path(&path, name), file));
+            }
+            for (name, subdirectory) in directory.iter_mut().collect::<Vec<_>>().into_iter().rev() {
+                self.stack.push((join_path(&path, name), subdirectory));
+            }
+        }
+    }
+}
+
+
+/// # Into Iter
+/// Iterator consuming a [`DirMulti`] into owned `(path, file)` pairs.
+///
+/// Paths are relative to the consumed directory. Directories are visited depth-first.
+pub struct IntoIter<T> {
+    stack: Vec<(String, DirMulti<T>)>,
+    buffer: VecDeque<(String, T)>,
+}
+impl <T> Iterator for IntoIter<T> {
+    type Item = (String, T);
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(item) = self.buffer.pop_front() { return Some(item) }
+            let (path, directory) = self.stack.pop()?;
+            for (name, file) in directory.file {
+                self.buffer.push_back((join_path(&path, &name), file));
+            }
+            for (name, subdirectory) in directory.directory.into_iter().collect::<Vec<_>>().into_iter().rev() {
+                self.stack.push((join_path(&path, &name), subdirectory));
+            }
+        }
+    }
+}
+
+
+// #===========================#
+// #=== DIR IMPLEMENTATIONS ===#
+
+impl <T> DirNode for DirMulti<T> {
+    type File = T;
+    fn subdirs(&self) -> impl Iterator<Item = (&String, &Self)> {
+        self.directory.iter()
+    }
+    fn files(&self) -> impl Iterator<Item = (Option<&String>, &T)> {
+        self.file.iter().map(|(name, file)| (Some(name), file))
+    }
+}
+impl <T> DirNode for DirSingle<T> {
+    type File = T;
+    fn subdirs(&self) -> impl Iterator<Item = (&String, &Self)> {
+        self.directory.iter()
+    }
+    fn files(&self) -> impl Iterator<Item = (Option<&String>, &T)> {
+        self.file.iter().map(|file| (None, file))
+    }
+}
+
+impl <T> DirMulti<T> {
+    /// Lazily walk over this directory and all subdirectories, yielding `(path, &directory)`
+    pub fn walk(&self) -> Walk<'_, DirMulti<T>> {
+        Walk::new(self)
+    }
+
+    /// Lazily iterate over all subdirectories, yielding `(path, &directory)`
+    pub fn iter_dirs(&self) -> IterDirs<'_, DirMulti<T>> {
+        IterDirs { walk: Walk::new(self) }
+    }
+
+    /// Lazily iterate over all files in this directory and subdirectories, yielding `(path, &file)`
+    pub fn iter_files(&self) -> IterFiles<'_, DirMulti<T>> {
+        IterFiles { walk: Walk::new(self), buffer: VecDeque::new() }
+    }
+
+    /// Lazily iterate over all files in this directory and subdirectories, yielding `(path, &mut file)`
+    pub fn iter_files_mut(&mut self) -> IterFilesMut<'_, T> {
+        IterFilesMut { stack: vec![(String::new(), self)], buffer: VecDeque::new() }
+    }
+
+    /// Inserts every file at its path, creating missing directories like `mkdir -p`. Existing files are replaced.
+    ///
+    /// Fallible form of [`Extend`], the first path that can't be used is returned as an error and the files before it stay inserted.
+    /// ```
+    /// # use pathio::prelude::*;
+    /// let mut tree: PathTree<u32> = PathTree::new("Root");
+    /// tree.try_extend([("a/one", 1), ("a/two", 2)]).unwrap();
+    /// let nested = tree.borrow_dir_mut("a").unwrap();
+    /// assert_eq!(nested.try_extend([("../../escape", 3)]), Err(DirError::InvalidPath("../../escape".to_owned())));
+    /// ```
+    pub fn try_extend<P: AsRef<TreePath>>(&mut self, iter: impl IntoIterator<Item = (P, T)>) -> Result<(), DirError> {
+        for (path, file) in iter {
+            let (names, name) = resolve_parent(self.get_path(), path.as_ref())?;
+            let directory = self.ensure_dir_mut(&names, &mut Vec::new())?;
+            if directory.link.contains_key(name) { return Err(DirError::NameInUse(join_names(&names, name))) }
+            directory.file.insert(name.to_owned(), file);
+        }
+        Ok(())
+    }
+
+    /// Depth-first walk over this directory and all subdirectories, calling the closure with `(path, &mut directory)` for each of them
+    ///
+    /// Directory is visited before its subdirectories, so changes made to the subdirectories in the closure are walked too.
+    pub fn walk_mut(&mut self, mut f: impl FnMut(&str, &mut DirMulti<T>)) {
+        let mut stack = vec![(String::new(), self)];
+        while let Some((path, directory)) = stack.pop() {
+            f(&path, directory);
+            for (name, subdirectory) in directory.directory.iter_mut().collect::<Vec<_>>().into_iter().rev() {
+                stack.push((join_path(&path, name), subdirectory));
+            }
+        }
+    }
+}
+impl <T> DirSingle<T> {
+    /// Lazily walk over this directory and all subdirectories, yielding `(path, &directory)`
+    pub fn walk(&self) -> Walk<'_, DirSingle<T>> {
+        Walk::new(self)
+    }
+
+    /// Lazily iterate over all subdirectories, yielding `(path, &directory)`
+    pub fn iter_dirs(&self) -> IterDirs<'_, DirSingle<T>> {
+        IterDirs { walk: Walk::new(self) }
+    }
+
+    /// Lazily iterate over all files in this directory and subdirectories, yielding `(path, &file)`
+    ///
+    /// The path of the file is the path of the directory holding it.
+    pub fn iter_files(&self) -> IterFiles<'_, DirSingle<T>> {
+        IterFiles { walk: Walk::new(self), buffer: VecDeque::new() }
+    }
+}
+
+impl <T> DirMapMulti<T> {
+    /// Lazily walk over the root directory and all subdirectories, yielding `(path, &directory)`
+    pub fn walk(&self) -> Walk<'_, DirMulti<T>> {
+        self.directory.walk()
+    }
+
+    /// Lazily iterate over all directories, yielding `(path, &directory)`
+    pub fn iter_dirs(&self) -> IterDirs<'_, DirMulti<T>> {
+        self.directory.iter_dirs()
+    }
+
+    /// Lazily iterate over all files, yielding `(path, &file)`
+    pub fn iter_files(&self) -> IterFiles<'_, DirMulti<T>> {
+        self.directory.iter_files()
+    }
+
+    /// Lazily iterate over all files, yielding `(path, &mut file)`
+    pub fn iter_files_mut(&mut self) -> IterFilesMut<'_, T> {
+        self.directory.iter_files_mut()
+    }
+
+    /// Depth-first walk over the root directory and all subdirectories, calling the closure with `(path, &mut directory)` for each of them
+    pub fn walk_mut(&mut self, f: impl FnMut(&str, &mut DirMulti<T>)) {
+        self.directory.walk_mut(f)
+    }
+
+    /// Inserts every file at its path, creating missing directories, see [`DirMulti::try_extend`]
+    pub fn try_extend<P: AsRef<TreePath>>(&mut self, iter: impl IntoIterator<Item = (P, T)>) -> Result<(), DirError> {
+        self.directory.try_extend(iter)
+    }
+
+    /// Builds an unnamed DirMap from `(path, file)` pairs, fallible form of [`FromIterator`]. See [`DirMulti::try_extend`] for details.
+    pub fn try_from_iter<P: AsRef<TreePath>>(iter: impl IntoIterator<Item = (P, T)>) -> Result<Self, DirError> {
+        let mut map = DirMapMulti::new("");
+        map.try_extend(iter)?;
+        Ok(map)
+    }
+}
+impl <T> DirMapSingle<T> {
+    /// Lazily walk over the root directory and all subdirectories, yielding `(path, &directory)`
+    pub fn walk(&self) -> Walk<'_, DirSingle<T>> {
+        self.directory.walk()
+    }
+
+    /// Lazily iterate over all directories, yielding `(path, &directory)`
+    pub fn iter_dirs(&self) -> IterDirs<'_, DirSingle<T>> {
+        self.directory.iter_dirs()
+    }
+
+    /// Lazily iterate over all files, yielding `(path, &file)`
+    pub fn iter_files(&self) -> IterFiles<'_, DirSingle<T>> {
+        self.directory.iter_files()
+    }
+}
+
+
+// #==================================#
+// #=== COLLECTION IMPLEMENTATIONS ===#
+
+impl <T> IntoIterator for DirMulti<T> {
+    type Item = (String, T);
+    type IntoIter = IntoIter<T>;
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter { stack: vec![(String::new(), self)], buffer: VecDeque::new() }
+    }
+}
+impl <'a, T> IntoIterator for &'a DirMulti<T> {
+    type Item = (String, &'a T);
+    type IntoIter = IterFiles<'a, DirMulti<T>>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_files()
+    }
+}
+impl <'a, T> IntoIterator for &'a mut DirMulti<T> {
+    type Item = (String, &'a mut T);
+    type IntoIter = IterFilesMut<'a, T>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_files_mut()
+    }
+}
+impl <T, P: AsRef<TreePath>> Extend<(P, T)> for DirMulti<T> {
+    /// Inserts every file at its path, creating missing directories like `mkdir -p`. Existing files are replaced.
+    ///
+    /// # Panics
+    /// Panics if a path can't be used, for example when it resolves outside of the directory, to the directory itself or to a link.
+    /// Use [`DirMulti::try_extend`] to handle these cases.
+    fn extend<I: IntoIterator<Item = (P, T)>>(&mut self, iter: I) {
+        for (path, file) in iter {
+            let path = path.as_ref();
+            if let Err(e) = self.try_extend([(path, file)]) { panic!("Unable to insert file '{path}': {e}") }
+        }
+    }
+}
+
+impl <T> IntoIterator for DirMapMulti<T> {
+    type Item = (String, T);
+    type IntoIter = IntoIter<T>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.directory.into_iter()
+    }
+}
+impl <'a, T> IntoIterator for &'a DirMapMulti<T> {
+    type Item = (String, &'a T);
+    type IntoIter = IterFiles<'a, DirMulti<T>>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_files()
+    }
+}
+impl <'a, T> IntoIterator for &'a mut DirMapMulti<T> {
+    type Item = (String, &'a mut T);
+    type IntoIter = IterFilesMut<'a, T>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_files_mut()
+    }
+}
+impl <T, P: AsRef<TreePath>> Extend<(P, T)> for DirMapMulti<T> {
+    /// Inserts every file at its path, creating missing directories like `mkdir -p`. Existing files are replaced.
+    ///
+    /// # Panics
+    /// Panics if a path can't be used, for example when it resolves outside of the directory, to the directory itself or to a link.
+    /// Use [`DirMapMulti::try_extend`] to handle these cases.
+    fn extend<I: IntoIterator<Item = (P, T)>>(&mut self, iter: I) {
+        self.directory.extend(iter)
+    }
+}
+impl <T, P: AsRef<TreePath>> FromIterator<(P, T)> for DirMapMulti<T> {
+    /// Builds an unnamed DirMap from `(path, file)` pairs, see [`Extend`] for details and [`DirMapMulti::try_from_iter`] for the fallible form.
+    fn from_iter<I: IntoIterator<Item = (P, T)>>(iter: I) -> Self {
+        let mut map = DirMapMulti::new("");
+        map.extend(iter);
+        map
+    }
+}
@@ -4,12 +4,72 @@
 mod tree;
 pub use tree::*;
 
+mod storage;
+pub use storage::*;
+
+mod iter;
+pub use iter::*;
+
+mod path;
+pub use path::*;
+
+mod glob;
+pub use glob::*;
+
+mod find;
+pub use find::*;
+
+mod format;
+pub use format::*;
+
+mod entry;
+pub use entry::*;
+
+mod retain;
+
+mod merge;
+pub use merge::*;
+
+mod diff;
+pub use diff::*;
+
+mod copy;
+pub use copy::*;
+
+mod link;
+pub use link::*;
+
+mod fs;
+pub use fs::*;
+
+mod flat;
+
+#[cfg(feature = "tar")]
+mod tarball;
+
+#[cfg(feature = "zip")]
+mod zipball;
+#[cfg(feature = "zip")]
+pub use zipball::*;
+
 pub mod prelude {
-    pub use crate::PathioHierarchy;
-    pub use crate::PathioFile;
-    pub use crate::PathioFileStorage;
-    pub use crate::PathTree;
+    pub use crate::DirError;
+    pub use crate::DirHierarchy;
+    pub use crate::DirFile;
+    pub use crate::DirFiles;
+    pub use crate::{PathTree, Directory};
+    pub use crate::DirShared;
     pub use crate::{PathTreeInit, DirectoryInit};
+    pub use crate::{TreePath, TreePathBuf};
+    pub use crate::{Pattern, Node};
+    pub use crate::{Finder, NodeKind};
+    pub use crate::{TreeFormat, TreeStyle};
+    pub use crate::{Entry, DirEntry};
+    pub use crate::{MergePolicy, MergeReport};
+    pub use crate::TreeChange;
+    pub use crate::{ImportOptions, ExportOptions, ExportMode, ExportReport};
+    #[cfg(feature = "zip")]
+    pub use crate::{ZipTree, ZipCompression};
 }
 
 #[cfg(test)]
@@ -18,19 +78,988 @@ pub mod test {
     #[test]
     fn directory () {
         let mut tree: PathTree<bool> = PathTree::new("Root");
-        tree.add_directory("added_directory", Directory::new()).unwrap();
-        tree.create_directory("created_directory").unwrap();
-        tree.insert_directory("created_directory/.inserted_directory", Directory::new()).unwrap();
+        tree.add_dir("added_directory", Directory::new()).unwrap();
+        tree.create_dir("created_directory").unwrap();
+        tree.insert_dir("created_directory/.inserted_directory", Directory::new()).unwrap();
 
-        tree.borrow_directory("created_directory/.inserted_directory").unwrap();
+        tree.borrow_dir("created_directory/.inserted_directory").unwrap();
 
         tree.tree();
 
-        tree.create_directory("created_directory/.inserted_directory/").unwrap();
+        tree.borrow_dir_mut("created_directory/.inserted_directory/").unwrap().create_dir("").unwrap();
         
-        assert_eq!(tree.borrow_directory("created_directory/.inserted_directory/.||#:0").unwrap().get_name(), ".||#:0");
+        assert_eq!(tree.borrow_dir("created_directory/.inserted_directory/.||#:0").unwrap().get_name(), ".||#:0");
+
+        assert_eq!(tree.borrow_dir("created_directory").unwrap(), tree.borrow_dir("created_directory/.").unwrap());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde () {
+        let mut tree: PathTree<usize> = PathTree::new("Root");
+        tree.create_dir("a").unwrap();
+        tree.create_dir("a/b").unwrap();
+        tree.insert_file("a/b/file", 5).unwrap();
+        tree.insert_file("top", 1).unwrap();
+
+        let json = serde_json::to_string(&tree).unwrap();
+        assert!(!json.contains("path") && !json.contains("depth"));
+
+        let loaded: PathTree<usize> = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded, tree);
+        assert_eq!(loaded.borrow_dir("a/b").unwrap().get_path(), "a/b");
+        assert_eq!(loaded.borrow_dir("a/b").unwrap().get_depth(), 2.0);
+
+        let mut single: DirMapSingle<usize> = DirMapSingle::new("Root");
+        single.create_dir("a").unwrap();
+        single.insert_file("a", 3).unwrap();
+        let loaded: DirMapSingle<usize> = serde_json::from_str(&serde_json::to_string(&single).unwrap()).unwrap();
+        assert_eq!(loaded, single);
+
+        assert!(serde_json::from_str::<PathTree<usize>>(r#"{"name":"Root","directory":{"a":{},"a":{}}}"#).is_err());
+        assert!(serde_json::from_str::<PathTree<usize>>(r#"{"name":"Root","directory":{"a/b":{}}}"#).is_err());
+        assert!(serde_json::from_str::<PathTree<usize>>(r#"{"name":"Root","path":"a"}"#).is_err());
+    }
+
+    #[test]
+    fn iterators () {
+        let mut tree: PathTree<usize> = PathTree::new("Root");
+        tree.create_dir("a").unwrap();
+        tree.create_dir("a/b").unwrap();
+        tree.create_dir("c").unwrap();
+        tree.insert_file("a/b/one", 1).unwrap();
+        tree.insert_file("c/two", 2).unwrap();
+        tree.insert_file("three", 3).unwrap();
+
+        let mut files: Vec<(String, usize)> = tree.iter_files().map(|(path, file)| (path, *file)).collect();
+        files.sort();
+        assert_eq!(files, vec![("a/b/one".to_owned(), 1), ("c/two".to_owned(), 2), ("three".to_owned(), 3)]);
+
+        let pre: Vec<String> = tree.walk().map(|(path, _)| path).collect();
+        assert_eq!(pre[0], "");
+        assert!(pre.iter().position(|p| p == "a").unwrap() < pre.iter().position(|p| p == "a/b").unwrap());
+
+        let post: Vec<String> = tree.walk().order(Traversal::DepthFirstPost).map(|(path, _)| path).collect();
+        assert_eq!(post.last().unwrap(), "");
+        assert!(post.iter().position(|p| p == "a/b").unwrap() < post.iter().position(|p| p == "a").unwrap());
+
+        let bfs: Vec<String> = tree.iter_dirs().order(Traversal::BreadthFirst).map(|(path, _)| path).collect();
+        assert_eq!(bfs.len(), 3);
+        assert_eq!(bfs[2], "a/b");
+
+        let found = tree.iter_files().find(|(_, file)| **file == 1).unwrap();
+        assert_eq!(found.0, "a/b/one");
+        assert_eq!(tree.borrow_dir("a").unwrap().iter_files().next().unwrap().0, "b/one");
+    }
+
+    #[test]
+    fn iterators_mut () {
+        let mut tree: PathTree<usize> = PathTree::new("Root");
+        tree.create_dir("a").unwrap();
+        tree.create_dir("a/b").unwrap();
+        tree.insert_file("a/b/one", 1).unwrap();
+        tree.insert_file("a/two", 2).unwrap();
+        tree.insert_file("three", 3).unwrap();
+
+        for (_, file) in tree.iter_files_mut() { *file *= 10; }
+        assert_eq!(tree.borrow_file("a/b/one"), Ok(&10));
+        assert_eq!(tree.iter_files().map(|(_, file)| *file).sum::<usize>(), 60);
+
+        let mut visited = Vec::new();
+        tree.walk_mut(|path, directory| {
+            visited.push(path.to_owned());
+            directory.add_file("marker", 0).unwrap();
+        });
+        visited.sort();
+        assert_eq!(visited, vec!["", "a", "a/b"]);
+        assert_eq!(tree.borrow_file("a/b/marker"), Ok(&0));
+    }
+
+    #[test]
+    fn collect () {
+        let tree: PathTree<usize> = [("a/b/one", 1), ("a/two", 2), ("three", 3)].into_iter().collect();
+        assert_eq!(tree.borrow_file("a/b/one"), Ok(&1));
+        assert_eq!(tree.borrow_dir("a/b").unwrap().get_path(), "a/b");
+
+        let mut tree = tree;
+        tree.extend([("a/two".to_owned(), 20), ("c/d/four".to_owned(), 4)]);
+        assert_eq!(tree.borrow_file("a/two"), Ok(&20));
+        assert_eq!(tree.borrow_file("c/d/four"), Ok(&4));
+
+        let mut files: Vec<(String, usize)> = tree.into_iter().collect();
+        files.sort();
+        assert_eq!(files, vec![("a/b/one".to_owned(), 1), ("a/two".to_owned(), 20), ("c/d/four".to_owned(), 4), ("three".to_owned(), 3)]);
+
+        let mut tree = PathTree::try_from_iter([("a/one", 1)]).unwrap();
+        tree.create_link("a/latest", "one").unwrap();
+        assert_eq!(tree.try_extend([("a/latest", 2)]), Err(DirError::NameInUse("a/latest".to_owned())));
+        assert_eq!(tree.try_extend([("..", 2)]), Err(DirError::InvalidPath("..".to_owned())));
+        assert_eq!(tree.try_extend([("/", 2)]), Err(DirError::InvalidPath("/".to_owned())));
+        assert_eq!(tree.borrow_dir_mut("a").unwrap().try_extend([("../../c", 2)]), Err(DirError::InvalidPath("../../c".to_owned())));
+        assert_eq!(PathTree::try_from_iter([("a/b", 1), ("", 2)]).err(), Some(DirError::InvalidPath("".to_owned())));
+        tree.validate().unwrap();
+        let panicked = std::panic::catch_unwind(move || tree.extend([("a/latest", 2)]));
+        assert!(panicked.is_err());
+    }
+
+    #[test]
+    fn create_all () {
+        let mut tree: PathTree<usize> = PathTree::new("Root");
+        tree.create_dir("a").unwrap();
+
+        assert_eq!(tree.create_dir_all("a/b/c").unwrap(), vec!["a/b", "a/b/c"]);
+        assert_eq!(tree.create_dir_all("a/b/c").unwrap(), Vec::<String>::new());
+        assert_eq!(tree.borrow_dir("a/b/c").unwrap().get_depth(), 3.0);
+
+        assert_eq!(tree.insert_file_with_parents("a/x/y/file", 1).unwrap(), vec!["a/x", "a/x/y"]);
+        assert_eq!(tree.insert_file_with_parents("a/x/y/file", 2), Err(DirError::NameInUse("a/x/y/file".to_owned())));
+        assert_eq!(tree.borrow_file("a/x/y/file"), Ok(&1));
+
+        let mut single: DirMapSingle<usize> = DirMapSingle::new("Root");
+        assert_eq!(single.insert_file_with_parents("a/b", 1).unwrap(), (vec!["a".to_owned(), "a/b".to_owned()], None));
+        assert_eq!(single.insert_file_with_parents("a/b", 2).unwrap(), (vec![], Some(1)));
+    }
 
-        assert_eq!(tree.borrow_directory("created_directory").unwrap(), tree.borrow_directory("created_directory/.").unwrap());
+    #[test]
+    fn move_and_rename () {
+        let mut tree: PathTree<usize> = PathTree::new("Root");
+        tree.create_dir_all("a/b/c").unwrap();
+        tree.create_dir("d").unwrap();
+        tree.insert_file("a/b/c/file", 1).unwrap();
+
+        assert_eq!(tree.rename_dir("a/b", "x").unwrap(), "a/x");
+        assert_eq!(tree.borrow_dir("a/x/c").unwrap().get_path(), "a/x/c");
+
+        assert_eq!(tree.move_dir("a/x", "d", false).unwrap(), "d/x");
+        let moved = tree.borrow_dir("d/x/c").unwrap();
+        assert_eq!((moved.get_path().as_str(), moved.get_depth()), ("d/x/c", 3.0));
+        assert_eq!(tree.borrow_file("d/x/c/file"), Ok(&1));
+
+        assert_eq!(tree.move_dir("d/x/c", "c", false).unwrap(), "c");
+        assert_eq!(tree.borrow_dir("c").unwrap().get_depth(), 1.0);
+
+        assert_eq!(tree.move_dir("d", "d/x/inside", false), Err(DirError::InvalidPath("d/x/inside".to_owned())));
+        tree.create_dir("e").unwrap();
+        tree.create_dir("d/e").unwrap();
+        assert_eq!(tree.move_dir("e", "d", false), Err(DirError::NameInUse("d/e".to_owned())));
+        assert_eq!(tree.move_dir("e", "d", true).unwrap(), "d/e");
+
+        assert_eq!(tree.rename_file("c/file", "renamed").unwrap(), "c/renamed");
+        tree.insert_file("other", 2).unwrap();
+        assert_eq!(tree.move_file("other", "c/renamed", false), Err(DirError::NameInUse("c/renamed".to_owned())));
+        assert_eq!(tree.move_file("other", "c/renamed", true).unwrap(), "c/renamed");
+        assert_eq!(tree.move_file("c/renamed", "d", false).unwrap(), "d/renamed");
+        assert_eq!(tree.borrow_file("d/renamed"), Ok(&2));
+
+        let mut single: DirMapSingle<usize> = DirMapSingle::new("Root");
+        single.create_dir_all("a/b").unwrap();
+        single.insert_file("a", 1).unwrap();
+        assert_eq!(single.move_file("a", "a/b", false).unwrap(), "a/b");
+        assert_eq!(single.borrow_file("a/b"), Ok(Some(&1)));
+    }
+
+    #[test]
+    fn cached_location () {
+        let mut branch: Directory<usize> = Directory::new();
+        branch.create_dir_all("x/y").unwrap();
+        assert_eq!(branch.borrow_dir("x/y").unwrap().get_path(), "x/y");
+
+        let mut tree: PathTree<usize> = PathTree::new("Root");
+        tree.create_dir("a").unwrap();
+        tree.insert_dir("a/branch", branch).unwrap();
+        assert_eq!(tree.borrow_dir("a/branch/x/y").unwrap().get_path(), "a/branch/x/y");
+        assert_eq!(tree.borrow_dir("a/branch/x/y").unwrap().get_depth(), 4.0);
+        tree.validate().unwrap();
+
+        let taken = tree.remove_dir("a/branch").unwrap();
+        assert_eq!(taken.get_path(), "");
+        assert_eq!(taken.borrow_dir("x/y").unwrap().get_path(), "x/y");
+        taken.validate().unwrap();
+
+        let mut other: PathTree<usize> = PathTree::new("Other");
+        other.create_dir_all("m/n").unwrap();
+        tree.borrow_dir_mut("a").unwrap().merge(other).unwrap();
+        assert_eq!(tree.borrow_dir("a/m/n").unwrap().get_path(), "a/m/n");
+        tree.validate().unwrap();
+
+        let stale = tree.remove_dir("a/m").unwrap();
+        tree.borrow_dir_mut("a").unwrap().directory.insert("m".to_owned(), stale);
+        assert_eq!(tree.validate(), Err(DirError::Desync("a/m".to_owned())));
+    }
+
+    #[test]
+    fn path_resolution () {
+        let mut tree: PathTree<usize> = PathTree::new("Root");
+        tree.create_dir_all("a/b/c").unwrap();
+        tree.create_dir("a//d/").unwrap();
+        tree.insert_file("a/./b/../d/file", 1).unwrap();
+
+        assert_eq!(*tree.borrow_file("/a/d/file").unwrap(), 1);
+        assert_eq!(tree.borrow_dir("a/b/c/../..").unwrap().get_path(), "a");
+        assert_eq!(tree.borrow_dir("..").unwrap().get_path(), "");
+        assert_eq!(tree.borrow_dir("a/b/c/../../../../a").unwrap().get_path(), "a");
+
+        let b = tree.borrow_dir("a/b").unwrap();
+        assert_eq!(b.borrow_dir("c/..").unwrap().get_path(), "a/b");
+        assert_eq!(b.borrow_dir("/a/b/c").unwrap().get_path(), "a/b/c");
+        assert_eq!(b.borrow_dir("../b/c").unwrap().get_path(), "a/b/c");
+        assert_eq!(b.borrow_dir("..").unwrap_err(), DirError::InvalidPath("..".to_owned()));
+        assert_eq!(b.borrow_dir("/a/d").unwrap_err(), DirError::InvalidPath("/a/d".to_owned()));
+
+        assert_eq!(tree.move_file("a/d/file", "/a/b/c/../", false).unwrap(), "a/b/file");
+        assert_eq!(tree.rename_dir("a/b/./c/", "e").unwrap(), "a/b/e");
+        assert_eq!(tree.remove_file("/a//b/file").unwrap(), 1);
+        assert!(tree.remove_dir("a/b/..").is_ok());
+        assert_eq!(tree.remove_dir("/").unwrap_err(), DirError::InvalidPath("/".to_owned()));
+
+        let mut single: DirMapSingle<usize> = DirMapSingle::new("Root");
+        single.create_dir_all("x/y").unwrap();
+        single.insert_file("x/y/..", 2).unwrap();
+        assert_eq!(single.borrow_file("x").unwrap(), Some(&2));
+        assert_eq!(single.borrow_file("/x/y").unwrap(), None);
+        tree.validate().unwrap();
+
+        assert_eq!(tree.add_dir("..", Directory::new()), Err(DirError::InvalidPath("..".to_owned())));
+        assert_eq!(tree.add_dir("x/y", Directory::new()), Err(DirError::InvalidPath("x/y".to_owned())));
+        assert_eq!(tree.add_file("t/f", 0), Err(DirError::InvalidPath("t/f".to_owned())));
+        assert_eq!(tree.rename_file("a", "t/f"), Err(DirError::InvalidPath("t/f".to_owned())));
+        assert_eq!(single.add_dir(".", DirSingle::new()), Err(DirError::InvalidPath(".".to_owned())));
+    }
+
+    #[test]
+    fn tree_path () {
+        let path = TreePathBuf::from("a//b/./c/");
+        assert_eq!(path.as_str(), "a/b/c");
+        assert_eq!(path.parent().unwrap(), TreePath::new("a/b"));
+        assert_eq!(path.file_name(), Some("c"));
+        assert_eq!(path.join("../d").as_str(), "a/b/d");
+        assert_eq!(path.join("/e").as_str(), "/e");
+        assert_eq!(path.strip_prefix("a/b").unwrap(), TreePath::new("c"));
+        assert!(path.strip_prefix("b").is_err());
+        assert_eq!(path.components().collect::<Vec<_>>(), vec![Component::Normal("a"), Component::Normal("b"), Component::Normal("c")]);
+        assert_eq!(TreePathBuf::from("../a/../..").as_str(), "../..");
+        assert_eq!(TreePath::new("/").parent(), None);
+        assert_eq!(TreePath::new("..").file_name(), None);
+
+        assert_eq!(TreePathBuf::from("a/../b"), *TreePath::new("a/../b"));
+        assert_ne!(TreePath::new("../b"), TreePath::new("b"));
+        let set: std::collections::HashSet<TreePathBuf> = [TreePathBuf::from("a/../b")].into();
+        assert!(set.contains(TreePath::new("./b/")));
+        assert_eq!(TreePathBuf::try_new("a/../b").unwrap().as_str(), "b");
+        assert_eq!(TreePathBuf::try_new("a/../../b"), Err(DirError::InvalidPath("a/../../b".to_owned())));
+
+        let mut buf = TreePathBuf::from("a");
+        buf.push("b");
+        assert!(buf.pop() && buf.pop() && !buf.pop());
+        assert!(buf.is_empty());
+
+        let mut tree: PathTree<usize> = PathTree::new("Root");
+        tree.create_dir_all(&path).unwrap();
+        tree.insert_file(path.join("file"), 1).unwrap();
+        tree.insert_file(String::from("a/file"), 2).unwrap();
+        assert_eq!(*tree.borrow_file(TreePath::new("/a/b/c/file")).unwrap(), 1);
+        assert_eq!(tree.borrow_dir(path.parent().unwrap()).unwrap().get_path(), "a/b");
     }
 
-}
\ No newline at end of file
+    #[test]
+    fn glob () {
+        let tree: PathTree<usize> = [
+            ("textures/logo.png", 1),
+            ("textures/ui/button.png", 2),
+            ("textures/ui/button.jpg", 3),
+            ("textures/ui/icons/close.png", 4),
+            ("models/logo.png", 5),
+            ("a1", 6),
+            ("b2", 7),
+        ].into_iter().collect();
+
+        let paths = |pattern: &str| tree.glob(pattern).unwrap().into_iter().map(|(path, _)| path).collect::<Vec<_>>();
+        assert_eq!(paths("textures/**/*.png"), vec!["textures/logo.png", "textures/ui/button.png", "textures/ui/icons/close.png"]);
+        assert_eq!(paths("*/logo.png"), vec!["models/logo.png", "textures/logo.png"]);
+        assert_eq!(paths("textures/ui/button.{png,jpg}"), vec!["textures/ui/button.jpg", "textures/ui/button.png"]);
+        assert_eq!(paths("[a-b]?"), vec!["a1", "b2"]);
+        assert_eq!(paths("[!a]*"), vec!["b2", "models", "textures"]);
+        assert_eq!(paths("textures/*"), vec!["textures/logo.png", "textures/ui"]);
+        assert_eq!(paths("**/icons"), vec!["textures/ui/icons"]);
+        assert_eq!(paths("**/ui/**").len(), 5);
+        assert_eq!(paths("**/*/**/close.png"), vec!["textures/ui/icons/close.png"]);
+
+        let found = tree.glob("textures/ui/icons/*").unwrap();
+        assert_eq!(found, vec![("textures/ui/icons/close.png".to_owned(), Node::File(&4))]);
+        assert!(tree.glob("textures/ui").unwrap()[0].1.is_dir());
+
+        assert_eq!(tree.glob("[ab").unwrap_err(), DirError::InvalidPattern("[ab".to_owned()));
+        assert!(tree.glob("{a,b").is_err());
+        assert!(tree.glob("../a").is_err());
+
+        let chain = vec!["d"; 80].join("/");
+        let mut deep: PathTree<usize> = PathTree::new("Root");
+        deep.create_dir_all(&chain).unwrap();
+        assert!(deep.glob("**/*/**/*/**/*/**/x").unwrap().is_empty());
+        assert_eq!(deep.glob("**/*/**/*/**/*/**/d").unwrap().len(), 77);
+        let pattern = Pattern::new("**/*/**/*/**/*/**/x").unwrap();
+        assert!(!pattern.matches(chain.as_str()));
+        assert!(pattern.matches(chain + "/x"));
+    }
+
+    #[test]
+    fn find () {
+        let mut tree: PathTree<usize> = [
+            ("a/one.txt", 1),
+            ("a/b/two.txt", 2),
+            ("a/b/c/three.bin", 3),
+            ("four.txt", 4),
+        ].into_iter().collect();
+        tree.create_dir_all("a/empty").unwrap();
+
+        let sorted = |finder: Finder<'_, usize>| { let mut paths: Vec<String> = finder.paths().collect(); paths.sort(); paths };
+        assert_eq!(sorted(tree.find().name(Pattern::new("*.txt").unwrap())), vec!["a/b/two.txt", "a/one.txt", "four.txt"]);
+        assert_eq!(sorted(tree.find().kind(NodeKind::Dir)), vec!["a", "a/b", "a/b/c", "a/empty"]);
+        assert_eq!(sorted(tree.find().depth(2.0..=2.0)), vec!["a/b", "a/empty", "a/one.txt"]);
+        assert_eq!(sorted(tree.find().depth(..2.0)), vec!["a", "four.txt"]);
+        assert_eq!(sorted(tree.find().file(|n| n % 2 == 1)), vec!["a/b/c/three.bin", "a/one.txt"]);
+        assert_eq!(sorted(tree.find().empty()), vec!["a/empty"]);
+        assert_eq!(tree.find().kind(NodeKind::File).limit(2).iter().count(), 2);
+
+        let b = tree.borrow_dir("a/b").unwrap();
+        let found: Vec<(String, Node<'_, usize>)> = b.find().depth(4.0..).into_iter().collect();
+        assert_eq!(found, vec![("c/three.bin".to_owned(), Node::File(&3))]);
+    }
+
+    #[test]
+    fn tree_format () {
+        let mut tree: PathTree<usize> = PathTree::new("Root");
+        tree.extend([("b/c/deep", 3), ("a/one", 1), ("top", 0), (".hidden/file", 4)]);
+
+        let plain = tree.tree_format().color(false);
+        assert_eq!(plain.to_string(), "> Root\n  |-> top\n  |-> a\n  |    |-> one\n  |-> b\n  |    |-> c\n  |    |    |-> deep");
+
+        let ascii = tree.tree_format().color(false).style(TreeStyle::Ascii).hidden(true).max_depth(2);
+        assert_eq!(ascii.to_string(), "Root\n|-- top\n|-- .hidden\n|   `-- file\n|-- a\n|   `-- one\n`-- b\n    `-- c");
+
+        let directories = tree.tree_format().color(false).style(TreeStyle::Unicode).files(false);
+        assert_eq!(directories.to_string(), "Root\n├── a\n└── b\n    └── c");
+        assert_eq!(tree.tree_dir(), tree.tree_format().files(false).to_string());
+
+        let mut single: DirMapSingle<usize> = DirMapSingle::new("Root");
+        single.insert_file_with_parents("x/y", 1).unwrap();
+        assert_eq!(single.tree_format().color(false).label(|n| format!("= {n}")).to_string(), "> Root\n  |-> x\n  |    |-> y\n  |    |    |-> FILE = 1");
+    }
+
+    #[cfg(any(feature = "sorted", feature = "insertion-order"))]
+    #[test]
+    fn storage_order () {
+        let mut tree: PathTree<usize> = PathTree::new("Root");
+        tree.extend([("c", 0), ("a", 1), ("b", 2)]);
+        tree.create_dir("z").unwrap();
+        tree.create_dir("x").unwrap();
+        tree.remove_file("a").unwrap();
+        tree.insert_file("a", 3).unwrap();
+
+        let files: Vec<&str> = tree.directory.file.keys().map(|name| name.as_str()).collect();
+        let directories: Vec<&str> = tree.directory.directory.keys().map(|name| name.as_str()).collect();
+        if cfg!(feature = "sorted") {
+            assert_eq!((files, directories), (vec!["a", "b", "c"], vec!["x", "z"]));
+        } else {
+            assert_eq!((files, directories), (vec!["c", "b", "a"], vec!["z", "x"]));
+        }
+
+        tree.create_dir("x/y").unwrap();
+        let (pre, post) = match cfg!(feature = "sorted") {
+            true => (vec!["", "x", "x/y", "z"], vec!["x/y", "x", "z", ""]),
+            false => (vec!["", "z", "x", "x/y"], vec!["z", "x/y", "x", ""]),
+        };
+        assert_eq!(tree.walk().map(|(path, _)| path).collect::<Vec<_>>(), pre);
+        assert_eq!(tree.walk().order(Traversal::DepthFirstPost).map(|(path, _)| path).collect::<Vec<_>>(), post);
+        let mut visited = Vec::new();
+        tree.walk_mut(|path, directory| {
+            visited.push(path.to_owned());
+            directory.add_file("marker", 0).unwrap();
+        });
+        assert_eq!(visited, pre);
+        let files: Vec<String> = tree.iter_files().map(|(path, _)| path).collect();
+        assert_eq!(tree.iter_files_mut().map(|(path, _)| path).collect::<Vec<_>>(), files);
+        assert_eq!(tree.into_iter().map(|(path, _)| path).collect::<Vec<_>>(), files);
+    }
+
+    #[test]
+    fn entry () {
+        let mut tree: PathTree<Vec<usize>> = PathTree::new("Root");
+        tree.create_dir("a").unwrap();
+
+        tree.entry("a/list").unwrap().or_default().push(1);
+        tree.entry("a/list").unwrap().or_insert_with(|| vec![0]).push(2);
+        tree.entry("/a/./list").unwrap().and_modify(|list| list.push(3)).or_insert(vec![0]);
+        assert_eq!(tree.borrow_file("a/list").unwrap(), &vec![1, 2, 3]);
+
+        match tree.entry("a/list").unwrap() {
+            Entry::Occupied(mut entry) => {
+                assert_eq!(entry.key(), "list");
+                assert_eq!(entry.insert(vec![4]), vec![1, 2, 3]);
+                assert_eq!(entry.remove(), vec![4]);
+            },
+            Entry::Vacant(_) => panic!("file should exist"),
+        }
+        assert!(matches!(tree.entry("a/list").unwrap(), Entry::Vacant(_)));
+        assert_eq!(tree.entry("missing/list").err(), Some(DirError::NoDir("missing".to_owned())));
+
+        tree.dir_entry("a/b").unwrap().or_default().create_dir("c").unwrap();
+        tree.dir_entry("a/b").unwrap().and_modify(|b| { b.create_dir("d").unwrap(); }).or_default();
+        assert_eq!(tree.borrow_dir("a/b/c").unwrap().get_path(), "a/b/c");
+        assert!(tree.borrow_dir("a/b/d").is_ok());
+        tree.validate().unwrap();
+
+        tree.create_link("a/latest", "b").unwrap();
+        assert_eq!(tree.entry("a/latest").err(), Some(DirError::NameInUse("a/latest".to_owned())));
+        assert_eq!(tree.dir_entry("a/latest").err(), Some(DirError::NameInUse("a/latest".to_owned())));
+        tree.validate().unwrap();
+    }
+
+    #[test]
+    fn retain_and_prune () {
+        let mut tree: PathTree<usize> = [
+            ("a/one", 1),
+            ("a/b/two", 2),
+            ("a/b/c/three", 3),
+            ("d/e/four", 4),
+            ("five", 5),
+        ].into_iter().collect();
+        tree.create_dir_all("f/g").unwrap();
+
+        tree.retain_files(|path, file| { *file *= 10; !path.starts_with("a/b") });
+        let mut files: Vec<(String, usize)> = tree.iter_files().map(|(path, file)| (path, *file)).collect();
+        files.sort();
+        assert_eq!(files, vec![("a/one".to_owned(), 10), ("d/e/four".to_owned(), 40), ("five".to_owned(), 50)]);
+
+        let mut removed = tree.prune_empty();
+        removed.sort();
+        assert_eq!(removed, vec!["a/b", "a/b/c", "f", "f/g"]);
+        assert!(tree.borrow_dir("a").is_ok() && tree.borrow_dir("d/e").is_ok());
+
+        tree.retain_dirs(|path, _| path != "d");
+        assert!(tree.borrow_dir("d").is_err());
+
+        let mut drained: Vec<(String, usize)> = tree.drain().collect();
+        drained.sort();
+        assert_eq!(drained, vec![("a/one".to_owned(), 10), ("five".to_owned(), 50)]);
+        assert!(tree.directory.file.is_empty() && tree.directory.directory.is_empty());
+    }
+
+    #[test]
+    fn merge_with () {
+        let base: PathTree<String> = [("cfg/name", "base"), ("cfg/audio/volume", "5"), ("keep", "k")].into_iter().map(|(p, v)| (p, v.to_owned())).collect();
+        let overlay: PathTree<String> = [("cfg/name", "overlay"), ("cfg/audio/volume", "7"), ("cfg/audio/mute", "no")].into_iter().map(|(p, v)| (p, v.to_owned())).collect();
+
+        let mut left = base.clone();
+        let report = left.merge_with(overlay.clone(), MergePolicy::KeepLeft).unwrap();
+        let mut conflicts: Vec<(String, MergeDecision)> = report.conflicts.into_iter().map(|c| (c.path, c.decision)).collect();
+        conflicts.sort_by(|a, b| a.0.cmp(&b.0));
+        assert_eq!(conflicts, vec![("cfg/audio/volume".to_owned(), MergeDecision::KeptLeft), ("cfg/name".to_owned(), MergeDecision::KeptLeft)]);
+        assert_eq!(left.borrow_file("cfg/name").unwrap(), "base");
+        assert_eq!(left.borrow_file("cfg/audio/mute").unwrap(), "no");
+        assert_eq!(left.borrow_dir("cfg/audio").unwrap().get_depth(), 2.0);
+        left.validate().unwrap();
+
+        let mut right = base.clone();
+        right.merge_with(overlay.clone(), MergePolicy::KeepRight).unwrap();
+        assert_eq!(right.borrow_file("cfg/audio/volume").unwrap(), "7");
+        assert_eq!(right.borrow_file("keep").unwrap(), "k");
+
+        let mut combined = base.clone();
+        let report = combined.merge_with(overlay.clone(), MergePolicy::combine(|_, left: &mut String, right| left.push_str(&right))).unwrap();
+        assert!(report.conflicts.iter().all(|c| c.decision == MergeDecision::Combined));
+        assert_eq!(combined.borrow_file("cfg/name").unwrap(), "baseoverlay");
+
+        let mut strict = base.clone();
+        assert!(matches!(strict.merge_with(overlay, MergePolicy::Error), Err(DirError::DuplicateName(_))));
+        assert_eq!(strict, base);
+
+        let mut single: DirMapSingle<usize> = DirMapSingle::new("Root");
+        single.insert_file_with_parents("a/b", 1).unwrap();
+        let mut other: DirMapSingle<usize> = DirMapSingle::new("Other");
+        other.insert_file_with_parents("a/b", 2).unwrap();
+        other.insert_file_with_parents("a/c", 3).unwrap();
+        let report = single.merge_with(other, MergePolicy::KeepRight).unwrap();
+        assert_eq!(report.conflicts, vec![MergeConflict { path: "a/b".to_owned(), decision: MergeDecision::KeptRight }]);
+        assert_eq!(single.borrow_file("a/c").unwrap(), Some(&3));
+    }
+
+    #[test]
+    fn diff_and_patch () {
+        let mut old: PathTree<usize> = [("a/one", 1), ("a/b/two", 2), ("gone/x/y", 3), ("top", 4)].into_iter().collect();
+        old.create_dir("empty").unwrap();
+        let mut new: PathTree<usize> = [("a/one", 1), ("a/b/two", 20), ("a/b/c/new", 5), ("fresh/z", 6)].into_iter().collect();
+        new.create_dir_all("fresh/empty").unwrap();
+
+        let changes = old.diff(&new);
+        assert_eq!(changes, vec![
+            TreeChange::RemovedFile("top".to_owned()),
+            TreeChange::ModifiedFile("a/b/two".to_owned(), 20),
+            TreeChange::AddedDir("a/b/c".to_owned()),
+            TreeChange::AddedFile("a/b/c/new".to_owned(), 5),
+            TreeChange::RemovedDir("empty".to_owned()),
+            TreeChange::AddedDir("fresh".to_owned()),
+            TreeChange::AddedFile("fresh/z".to_owned(), 6),
+            TreeChange::AddedDir("fresh/empty".to_owned()),
+            TreeChange::RemovedDir("gone".to_owned()),
+        ]);
+        assert_eq!(changes[2].path(), "a/b/c");
+
+        let mut patched = old.clone();
+        patched.apply_patch(changes.clone()).unwrap();
+        assert!(patched.diff(&new).is_empty());
+        assert_eq!(patched.directory, new.directory);
+        patched.validate().unwrap();
+
+        assert_eq!(old.apply_patch(vec![TreeChange::RemovedDir("missing".to_owned())]), Err(DirError::NoDir("missing".to_owned())));
+
+        #[cfg(feature = "serde")]
+        {
+            let json = serde_json::to_string(&changes).unwrap();
+            assert_eq!(serde_json::from_str::<Vec<TreeChange<usize>>>(&json).unwrap(), changes);
+        }
+    }
+
+    #[test]
+    fn copy_dir () {
+        let mut tree: PathTree<usize> = [("skeleton/config/a", 1), ("skeleton/b", 2)].into_iter().collect();
+        tree.create_dir_all("users/alice").unwrap();
+        tree.create_dir("users/bob").unwrap();
+
+        assert_eq!(tree.copy_dir("skeleton", "users/alice", false).unwrap(), "users/alice/skeleton");
+        assert_eq!(tree.copy_dir("skeleton", "users/bob/home", false).unwrap(), "users/bob/home");
+        assert_eq!(*tree.borrow_file("users/bob/home/config/a").unwrap(), 1);
+        assert_eq!(tree.borrow_dir("users/bob/home/config").unwrap().get_depth(), 4.0);
+        assert_eq!(tree.borrow_dir("users/alice/skeleton/config").unwrap().get_path(), "users/alice/skeleton/config");
+
+        *tree.borrow_file_mut("users/bob/home/b").unwrap() = 20;
+        assert_eq!(*tree.borrow_file("skeleton/b").unwrap(), 2);
+
+        assert_eq!(tree.copy_dir("skeleton", "users/alice", false).unwrap_err(), DirError::NameInUse("users/alice/skeleton".to_owned()));
+        assert_eq!(tree.copy_dir("skeleton", "skeleton/config", false).unwrap_err(), DirError::InvalidPath("skeleton/config/skeleton".to_owned()));
+        tree.create_dir_all("users/carol/skeleton").unwrap();
+        assert!(tree.copy_dir("skeleton", "users/carol", false).is_err());
+        assert_eq!(tree.copy_dir("skeleton", "users/carol", true).unwrap(), "users/carol/skeleton");
+        assert_eq!(*tree.borrow_file("users/carol/skeleton/b").unwrap(), 2);
+        tree.validate().unwrap();
+
+        let mut single: DirMapSingle<usize> = DirMapSingle::new("Root");
+        single.insert_file_with_parents("a/b", 1).unwrap();
+        assert_eq!(single.copy_dir("a", "c", false).unwrap(), "c");
+        assert_eq!(single.borrow_file("c/b").unwrap(), Some(&1));
+        single.validate().unwrap();
+
+        let template: PathTree<usize> = [("skeleton/config/a", 1), ("skeleton/b", 2)].into_iter().collect();
+        let mut shared = DirShared::from(template);
+        assert_eq!(shared.create_dir_all("users/alice").unwrap(), vec!["users".to_owned(), "users/alice".to_owned()]);
+        for i in 0..1000 {
+            shared.copy_dir("skeleton", format!("users/{i}"), false).unwrap();
+        }
+        assert!(std::ptr::eq(shared.borrow_dir("users/999").unwrap(), shared.borrow_dir("skeleton").unwrap()));
+        assert_eq!(shared.copy_dir("skeleton", "users/alice", false).unwrap(), "users/alice/skeleton");
+        assert_eq!(shared.borrow_dir("users/alice/skeleton/config").unwrap().get_path(), "skeleton/config");
+
+        *shared.borrow_file_mut("users/7/config/a").unwrap() = 10;
+        assert_eq!(*shared.borrow_file("users/7/config/a").unwrap(), 10);
+        assert_eq!(*shared.borrow_file("users/8/config/a").unwrap(), 1);
+        assert_eq!(*shared.borrow_file("skeleton/config/a").unwrap(), 1);
+        assert_eq!(shared.borrow_dir("users/7/config").unwrap().get_path(), "users/7/config");
+        assert_eq!(shared.borrow_dir("users/7/config").unwrap().get_depth(), 3.0);
+        assert!(!std::ptr::eq(shared.borrow_dir("users/7").unwrap(), shared.borrow_dir("skeleton").unwrap()));
+        assert!(std::ptr::eq(shared.borrow_file("users/7/b").unwrap(), shared.borrow_file("skeleton/b").unwrap()));
+
+        shared.insert_file("users/8/c", 3).unwrap();
+        assert_eq!(shared.remove_file("users/9/b").unwrap(), 2);
+        assert_eq!(shared.borrow_file("users/9/b").unwrap_err(), DirError::NoFile("b".to_owned()));
+        assert_eq!(*shared.borrow_file("skeleton/b").unwrap(), 2);
+        assert_eq!(shared.borrow_file("skeleton/c").unwrap_err(), DirError::NoFile("c".to_owned()));
+        assert_eq!(shared.copy_dir("skeleton", "users/alice", false).unwrap_err(), DirError::NameInUse("users/alice/skeleton".to_owned()));
+        assert_eq!(shared.copy_dir("skeleton", "skeleton/config", false).unwrap_err(), DirError::InvalidPath("skeleton/config/skeleton".to_owned()));
+        assert_eq!(shared.create_dir("users/8").unwrap_err(), DirError::NameInUse("users/8".to_owned()));
+        assert_eq!(shared.remove_dir("users/0").unwrap().get_path(), "");
+
+        let tree = PathTree::from(shared);
+        tree.validate().unwrap();
+        assert_eq!(*tree.borrow_file("users/7/config/a").unwrap(), 10);
+        assert_eq!(*tree.borrow_file("users/8/c").unwrap(), 3);
+        assert_eq!(tree.borrow_dir("users/alice/skeleton/config").unwrap().get_path(), "users/alice/skeleton/config");
+        assert!(tree.borrow_dir("users/0").is_err());
+        assert_eq!(tree.iter_files().count(), 2 + 1000 * 2);
+    }
+
+    #[test]
+    fn link () {
+        let mut tree: PathTree<usize> = [("packages/v1/manifest", 1), ("packages/v2/manifest", 2)].into_iter().collect();
+        assert_eq!(tree.create_link("packages/latest", "v2").unwrap(), "packages/latest");
+        assert_eq!(tree.create_link("aliases/stable", "/packages/v1"), Err(DirError::NoDir("aliases".to_owned())));
+        tree.create_dir("aliases").unwrap();
+        tree.create_link("aliases/stable", "/packages/v1").unwrap();
+        tree.create_link("aliases/newest", "../packages/latest").unwrap();
+        tree.create_link("aliases/config", "newest/manifest").unwrap();
+
+        assert_eq!(*tree.borrow_file("packages/latest/manifest").unwrap(), 2);
+        assert_eq!(*tree.borrow_file("aliases/stable/manifest").unwrap(), 1);
+        assert_eq!(*tree.borrow_file("aliases/config").unwrap(), 2);
+        assert_eq!(tree.borrow_dir("aliases/newest").unwrap().get_path(), "packages/v2");
+        *tree.borrow_file_mut("aliases/config").unwrap() = 20;
+        assert_eq!(*tree.borrow_file("packages/v2/manifest").unwrap(), 20);
+        tree.borrow_dir_mut("aliases/stable").unwrap().add_file("extra", 3).unwrap();
+        assert_eq!(*tree.borrow_file("packages/v1/extra").unwrap(), 3);
+
+        assert_eq!(tree.borrow_dir_nofollow("aliases/newest"), Err(DirError::NoDir("newest".to_owned())));
+        assert_eq!(tree.borrow_dir_nofollow("aliases/newest/..").unwrap().get_path(), "aliases");
+        assert_eq!(tree.borrow_file_nofollow("aliases/config"), Err(DirError::NoFile("config".to_owned())));
+        assert_eq!(*tree.borrow_file_nofollow("aliases/newest/manifest").unwrap(), 20);
+        assert_eq!(tree.read_link("aliases/newest").unwrap(), TreePath::new("../packages/latest"));
+        assert_eq!(tree.read_link("packages/v1"), Err(DirError::NoLink("packages/v1".to_owned())));
+        assert_eq!(tree.create_link("packages/v1", "v2"), Err(DirError::NameInUse("packages/v1".to_owned())));
+
+        tree.create_link("loop/a", "b").unwrap_err();
+        tree.create_dir("loop").unwrap();
+        tree.create_link("loop/a", "b").unwrap();
+        tree.create_link("loop/b", "a").unwrap();
+        assert_eq!(tree.borrow_dir("loop/a"), Err(DirError::LinkLoop("loop/a".to_owned())));
+        assert_eq!(tree.borrow_file("loop/a/file"), Err(DirError::LinkLoop("loop/a".to_owned())));
+        tree.create_link("dangling", "missing").unwrap();
+        assert_eq!(tree.borrow_dir("dangling"), Err(DirError::NoDir("missing".to_owned())));
+
+        let subtree = tree.borrow_dir("aliases").unwrap();
+        assert_eq!(subtree.borrow_dir("stable"), Err(DirError::InvalidPath("/packages/v1".to_owned())));
+
+        assert_eq!(tree.remove_link("loop/a").unwrap(), TreePathBuf::from("b"));
+        assert_eq!(tree.borrow_dir("loop/b"), Err(DirError::NoDir("a".to_owned())));
+
+        tree.create_dir("clash").unwrap();
+        tree.create_link("clash/x", "/packages").unwrap();
+        assert_eq!(tree.insert_file("clash/x", 1), Err(DirError::NameInUse("x".to_owned())));
+        assert_eq!(tree.create_dir("clash/x"), Err(DirError::NameInUse("x".to_owned())));
+        tree.insert_file("clash/y", 2).unwrap();
+        assert_eq!(tree.create_link("clash/y", "/packages"), Err(DirError::NameInUse("clash/y".to_owned())));
+        assert_eq!(tree.rename_file("clash/y", "x"), Err(DirError::NameInUse("clash/x".to_owned())));
+        assert_eq!(tree.move_dir("packages/v1", "clash/x", true), Err(DirError::NameInUse("clash/x".to_owned())));
+
+        let mut single: DirMapSingle<usize> = DirMapSingle::new("Root");
+        single.insert_file_with_parents("v1", 1).unwrap();
+        single.create_link("latest", "v1").unwrap();
+        assert_eq!(single.borrow_file("latest").unwrap(), Some(&1));
+        assert!(single.borrow_dir_nofollow("latest").is_err());
+        assert_eq!(single.borrow_file_nofollow("latest"), Err(DirError::NoDir("latest".to_owned())));
+        assert_eq!(single.borrow_file_nofollow("v1"), Ok(Some(&1)));
+        assert_eq!(single.create_dir("latest"), Err(DirError::NameInUse("latest".to_owned())));
+
+        let base: PathTree<usize> = [("v1/manifest", 1), ("v2/manifest", 2)].into_iter().collect();
+        let mut linked = base.clone();
+        linked.create_link("latest", "v2").unwrap();
+        assert_ne!(base, linked);
+        assert_eq!(base.diff(&linked), vec![TreeChange::AddedLink("latest".to_owned(), TreePathBuf::from("v2"))]);
+        assert_eq!(linked.diff(&base), vec![TreeChange::RemovedLink("latest".to_owned())]);
+        let mut moved = base.clone();
+        moved.create_dir("latest").unwrap();
+        moved.create_link("v2/current", "..").unwrap();
+        let changes = linked.diff(&moved);
+        assert_eq!(changes[0], TreeChange::RemovedLink("latest".to_owned()));
+        let mut patched = linked.clone();
+        patched.apply_patch(changes).unwrap();
+        assert_eq!(patched, moved);
+        let mut retargeted = linked.clone();
+        retargeted.remove_link("latest").unwrap();
+        retargeted.create_link("latest", "v1").unwrap();
+        assert_eq!(linked.diff(&retargeted), vec![TreeChange::ModifiedLink("latest".to_owned(), TreePathBuf::from("v1"))]);
+
+        let mut overlay: DirMulti<usize> = DirMulti::new();
+        overlay.create_link("latest", "v1").unwrap();
+        let mut merged = base.clone();
+        merged.merge_with(linked.clone(), MergePolicy::KeepLeft).unwrap();
+        assert_eq!(merged, linked);
+        let report = merged.merge_with(overlay.clone(), MergePolicy::KeepRight).unwrap();
+        assert_eq!(report.conflicts, vec![MergeConflict { path: "latest".to_owned(), decision: MergeDecision::KeptRight }]);
+        assert_eq!(merged.read_link("latest").unwrap(), TreePath::new("v1"));
+        assert_eq!(linked.clone().merge_with(overlay.clone(), MergePolicy::Error), Err(DirError::DuplicateName("latest".to_owned())));
+        let mut clashing = linked.clone();
+        assert_eq!(clashing.merge_with(moved.clone(), MergePolicy::KeepLeft), Err(DirError::DuplicateName("latest".to_owned())));
+        assert_eq!(clashing, linked);
+        let mut shallow = base.clone();
+        shallow.merge(overlay.clone()).unwrap();
+        assert_eq!(shallow.read_link("latest").unwrap(), TreePath::new("v1"));
+        assert_eq!(linked.clone().merge(overlay), Err(DirError::DuplicateName("latest".to_owned())));
+
+        let mut pruned = linked.clone();
+        pruned.create_dir("only").unwrap();
+        pruned.create_link("only/link", "/v1").unwrap();
+        pruned.create_dir("none").unwrap();
+        assert_eq!(pruned.prune_empty(), vec!["none"]);
+        assert_eq!(pruned.find().empty().paths().count(), 0);
+        pruned.directory.borrow_dir_mut("only").unwrap().file.insert("link".to_owned(), 1);
+        assert_eq!(pruned.validate(), Err(DirError::NameInUse("only/link".to_owned())));
+
+        let mut drained = linked.clone();
+        assert_eq!(drained.drain().count(), 2);
+        assert!(drained.directory.link.is_empty());
+        assert_eq!(drained.read_link("latest"), Err(DirError::NoLink("latest".to_owned())));
+
+        let mut overwritten: PathTree<usize> = [("src/x/file", 1)].into_iter().collect();
+        overwritten.create_dir_all("dst/x").unwrap();
+        overwritten.create_link("dst/x/keep", "/src").unwrap();
+        assert_eq!(overwritten.move_dir("src/x", "dst", true), Err(DirError::NameInUse("dst/x".to_owned())));
+        assert_eq!(overwritten.copy_dir("src/x", "dst", true), Err(DirError::NameInUse("dst/x".to_owned())));
+        assert_eq!(overwritten.read_link("dst/x/keep").unwrap(), TreePath::new("/src"));
+        let mut single: DirMapSingle<usize> = DirMapSingle::new("Root");
+        single.insert_file_with_parents("src/x", 1).unwrap();
+        single.create_dir_all("dst/x").unwrap();
+        single.create_link("dst/x/keep", "/src").unwrap();
+        assert_eq!(single.move_dir("src/x", "dst", true), Err(DirError::NameInUse("dst/x".to_owned())));
+        assert_eq!(single.copy_dir("src/x", "dst", true), Err(DirError::NameInUse("dst/x".to_owned())));
+
+        #[cfg(feature = "serde")]
+        {
+            let json = serde_json::to_string(&tree).unwrap();
+            let restored: PathTree<usize> = serde_json::from_str(&json).unwrap();
+            assert_eq!(restored, tree);
+            assert_eq!(*restored.borrow_file("aliases/config").unwrap(), 20);
+        }
+    }
+
+    #[test]
+    fn from_fs () {
+        let root = std::env::temp_dir().join(format!("pathio-from-fs-{}", std::process::id()));
+        std::fs::create_dir_all(root.join("assets/textures/ui")).unwrap();
+        std::fs::create_dir_all(root.join("assets/.cache")).unwrap();
+        std::fs::write(root.join("assets/readme.md"), "readme").unwrap();
+        std::fs::write(root.join("assets/textures/grass.png"), "grass").unwrap();
+        std::fs::write(root.join("assets/textures/ui/button.png"), "button").unwrap();
+        std::fs::write(root.join("assets/textures/ui/button.tmp"), "tmp").unwrap();
+        std::fs::write(root.join("assets/.cache/index"), "index").unwrap();
+
+        let tree: PathTree<String> = PathTree::from_fs(root.join("assets"), |path| std::fs::read_to_string(path)).unwrap();
+        assert_eq!(tree.get_name(), "assets");
+        assert_eq!(tree.borrow_file("textures/ui/button.png").unwrap(), "button");
+        assert_eq!(tree.borrow_file(".cache/index").unwrap(), "index");
+        assert_eq!(tree.iter_files().count(), 5);
+        tree.validate().unwrap();
+
+        let options = ImportOptions::new().hidden(false).max_depth(2).ignore(Pattern::new("**/*.tmp").unwrap());
+        let tree: PathTree<usize> = PathTree::from_fs_with(root.join("assets"), &options, |path| std::fs::metadata(path).map(|m| m.len() as usize)).unwrap();
+        assert_eq!(tree.borrow_file("textures/grass.png"), Ok(&5));
+        assert!(tree.borrow_dir("textures/ui").unwrap().file.is_empty());
+        assert!(tree.borrow_dir(".cache").is_err());
+        let options = ImportOptions::new().ignore(Pattern::new("textures").unwrap());
+        let tree: PathTree<()> = PathTree::from_fs_with(root.join("assets"), &options, |_| Ok(())).unwrap();
+        assert!(tree.borrow_dir("textures").is_err());
+
+        #[cfg(unix)]
+        {
+            std::os::unix::fs::symlink("textures/ui", root.join("assets/latest")).unwrap();
+            let tree: PathTree<String> = PathTree::from_fs(root.join("assets"), |path| std::fs::read_to_string(path)).unwrap();
+            assert_eq!(tree.read_link("latest").unwrap(), TreePath::new("textures/ui"));
+            assert_eq!(tree.borrow_file("latest/button.png").unwrap(), "button");
+
+            let options = ImportOptions::new().follow_links(true);
+            let tree: PathTree<String> = PathTree::from_fs_with(root.join("assets"), &options, |path| std::fs::read_to_string(path)).unwrap();
+            assert!(tree.read_link("latest").is_err());
+            assert_eq!(tree.borrow_file("latest/button.png").unwrap(), "button");
+
+            std::os::unix::fs::symlink("..", root.join("assets/textures/parent")).unwrap();
+            let result: Result<PathTree<String>, DirError> = PathTree::from_fs_with(root.join("assets"), &options, |path| std::fs::read_to_string(path));
+            assert_eq!(result.unwrap_err(), DirError::LinkLoop("textures/parent".to_owned()));
+        }
+
+        assert!(matches!(PathTree::<()>::from_fs(root.join("missing"), |_| Ok(())), Err(DirError::Io(_))));
+        std::fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn write_to_fs () {
+        let root = std::env::temp_dir().join(format!("pathio-write-to-fs-{}", std::process::id()));
+        let target = root.join("scaffold");
+        let writer = |text: &String| Ok(text.as_bytes().to_vec());
+        let mut tree: PathTree<String> = PathTree::new("scaffold");
+        tree.insert_file_with_parents("src/main.rs", "fn main() {}".to_owned()).unwrap();
+        tree.insert_file_with_parents("Cargo.toml", "[package]".to_owned()).unwrap();
+        tree.create_dir_all("assets/empty").unwrap();
+
+        let report = tree.write_to_fs(&target, writer).unwrap();
+        assert_eq!(report.created_dirs, ["assets", "assets/empty", "src"]);
+        assert_eq!(report.written, ["Cargo.toml", "src/main.rs"]);
+        assert_eq!(std::fs::read_to_string(target.join("src/main.rs")).unwrap(), "fn main() {}");
+        assert!(target.join("assets/empty").is_dir());
+        assert_eq!(PathTree::from_fs(&target, |path| std::fs::read_to_string(path)).unwrap(), tree);
+
+        std::fs::write(target.join("notes.txt"), "notes").unwrap();
+        std::fs::create_dir(target.join("old")).unwrap();
+        std::fs::write(target.join("old/file"), "old").unwrap();
+        assert_eq!(tree.write_to_fs(&target, writer).unwrap_err(), DirError::NameInUse("Cargo.toml".to_owned()));
+
+        *tree.borrow_file_mut("Cargo.toml").unwrap() = "[workspace]".to_owned();
+        let report = tree.write_to_fs_with(&target, &ExportOptions::new().mode(ExportMode::Overwrite).dry_run(true), writer).unwrap();
+        assert!(report.dry_run && report.created_dirs.is_empty() && report.written.is_empty());
+        assert_eq!(report.overwritten, ["Cargo.toml", "src/main.rs"]);
+        assert_eq!(std::fs::read_to_string(target.join("Cargo.toml")).unwrap(), "[package]");
+
+        tree.write_to_fs_with(&target, &ExportOptions::new().mode(ExportMode::Overwrite), writer).unwrap();
+        assert_eq!(std::fs::read_to_string(target.join("Cargo.toml")).unwrap(), "[workspace]");
+        assert!(target.join("notes.txt").exists());
+
+        let report = tree.write_to_fs_with(&target, &ExportOptions::new().mode(ExportMode::Clean), writer).unwrap();
+        assert_eq!(report.removed, ["notes.txt", "old"]);
+        assert!(!target.join("notes.txt").exists() && !target.join("old").exists());
+        assert_eq!(PathTree::from_fs(&target, |path| std::fs::read_to_string(path)).unwrap(), tree);
+
+        #[cfg(unix)]
+        {
+            tree.create_link("src/lib", "/src/main.rs").unwrap();
+            let report = tree.write_to_fs_with(&target, &ExportOptions::new().mode(ExportMode::Overwrite), writer).unwrap();
+            assert_eq!(report.linked, ["src/lib"]);
+            assert_eq!(std::fs::read_link(target.join("src/lib")).unwrap(), std::path::Path::new("../src/main.rs"));
+            assert_eq!(std::fs::read_to_string(target.join("src/lib")).unwrap(), "fn main() {}");
+            assert!(report.skipped_links.is_empty());
+
+            tree.create_link("src/assets", "/assets").unwrap();
+            let report = tree.borrow_dir("src").unwrap().write_to_fs(root.join("src"), writer).unwrap();
+            assert_eq!(report.linked, ["lib"]);
+            assert_eq!(report.skipped_links, ["assets"]);
+            assert!(!root.join("src/assets").exists());
+            std::fs::remove_dir_all(root.join("src")).unwrap();
+        }
+
+        let failing = tree.write_to_fs(root.join("failing"), |_| Err(std::io::Error::other("refused")));
+        assert!(matches!(failing, Err(DirError::Io(_))));
+        assert!(!root.join("failing").exists());
+        assert_eq!(std::fs::read_dir(&root).unwrap().count(), 1);
+        std::fs::remove_dir_all(root).unwrap();
+    }
+
+    #[cfg(feature = "tar")]
+    #[test]
+    fn tar () {
+        let mut tree: PathTree<Vec<u8>> = PathTree::new("Root");
+        tree.insert_file_with_parents("packages/v1/manifest", b"v1".to_vec()).unwrap();
+        tree.insert_file_with_parents("readme", b"readme".to_vec()).unwrap();
+        tree.create_dir_all("empty/nested").unwrap();
+        tree.create_link("packages/latest", "v1").unwrap();
+
+        let mut archive = Vec::new();
+        tree.write_tar(&mut archive).unwrap();
+        let restored = PathTree::<Vec<u8>>::from_tar("Root", archive.as_slice()).unwrap();
+        assert_eq!(restored, tree);
+        assert_eq!(restored.borrow_file("packages/latest/manifest").unwrap(), b"v1");
+        restored.validate().unwrap();
+
+        let mut paths = Vec::new();
+        for entry in tar::Archive::new(archive.as_slice()).entries().unwrap() {
+            let entry = entry.unwrap();
+            paths.push((entry.path().unwrap().to_string_lossy().into_owned(), entry.header().entry_type()));
+        }
+        assert!(paths.contains(&("packages/v1/manifest".to_owned(), tar::EntryType::Regular)));
+        assert!(paths.contains(&("packages/latest".to_owned(), tar::EntryType::Symlink)));
+        assert!(paths.contains(&("empty/nested".to_owned(), tar::EntryType::Directory)));
+
+        let mut builder = tar::Builder::new(Vec::new());
+        for (path, data) in [("./a/b/file", "old"), ("a/b/file", "new")] {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(data.len() as u64);
+            builder.append_data(&mut header, path, data.as_bytes()).unwrap();
+        }
+        let mut header = tar::Header::new_gnu();
+        header.set_entry_type(tar::EntryType::Symlink);
+        header.set_size(0);
+        builder.append_link(&mut header, "a/absolute", "/etc/passwd").unwrap();
+        let archive = builder.into_inner().unwrap();
+        let mut merged: PathTree<Vec<u8>> = PathTree::new("Root");
+        merged.insert_file("existing", b"kept".to_vec()).unwrap();
+        merged.read_tar(archive.as_slice()).unwrap();
+        assert_eq!(merged.borrow_file("a/b/file").unwrap(), b"new");
+        assert_eq!(merged.borrow_file("existing").unwrap(), b"kept");
+        assert!(merged.read_link("a/absolute").is_err());
+
+        let mut builder = tar::Builder::new(Vec::new());
+        let mut header = tar::Header::new_gnu();
+        header.set_size(0);
+        header.as_gnu_mut().unwrap().name[..4].copy_from_slice(b"../x");
+        header.set_cksum();
+        builder.append(&header, std::io::empty()).unwrap();
+        let archive = builder.into_inner().unwrap();
+        assert!(PathTree::<Vec<u8>>::from_tar("Root", archive.as_slice()).unwrap().borrow_file("x").is_ok());
+        let mut nested: PathTree<Vec<u8>> = PathTree::new("Root");
+        nested.create_dir("nested").unwrap();
+        assert_eq!(nested.borrow_dir_mut("nested").unwrap().read_tar(archive.as_slice()), Err(DirError::InvalidPath("../x".to_owned())));
+
+        for paths in [["a", "a/b"], ["a/b", "a"]] {
+            let mut builder = tar::Builder::new(Vec::new());
+            for path in paths {
+                let mut header = tar::Header::new_gnu();
+                header.set_size(0);
+                builder.append_data(&mut header, path, std::io::empty()).unwrap();
+            }
+            let archive = builder.into_inner().unwrap();
+            assert_eq!(PathTree::<Vec<u8>>::from_tar("Root", archive.as_slice()), Err(DirError::AmbiguousPath("a".to_owned())));
+        }
+    }
+
+    #[cfg(feature = "zip")]
+    #[test]
+    fn zip () {
+        let mut tree: PathTree<Vec<u8>> = PathTree::new("mod");
+        tree.insert_file_with_parents("textures/sword.png", vec![7; 64]).unwrap();
+        tree.insert_file_with_parents("scripts/init.lua", b"print('hi')".to_vec()).unwrap();
+        tree.create_dir_all("empty/nested").unwrap();
+        tree.create_link("latest", "scripts").unwrap();
+
+        let compression = |path: &str, _: &Vec<u8>| if path.ends_with(".png") { ZipCompression::Stored } else { ZipCompression::DeflatedLevel(9) };
+        let archive = tree.write_zip_with(std::io::Cursor::new(Vec::new()), compression).unwrap();
+        let mut raw = zip::ZipArchive::new(archive.clone()).unwrap();
+        assert_eq!(raw.by_name("textures/sword.png").unwrap().compression(), zip::CompressionMethod::Stored);
+        assert_eq!(raw.by_name("scripts/init.lua").unwrap().compression(), zip::CompressionMethod::Deflated);
+        assert!(raw.by_name("latest").unwrap().is_symlink());
+
+        let mut lazy = ZipTree::new("mod", archive).unwrap();
+        assert!(lazy.structure().borrow_dir("empty/nested").is_ok());
+        assert!(lazy.structure().iter_files().all(|(_, entry)| !entry.is_loaded()));
+        assert_eq!(lazy.borrow_file("scripts/init.lua").unwrap(), b"print('hi')");
+        assert!(lazy.structure().borrow_file("scripts/init.lua").unwrap().is_loaded());
+        assert!(!lazy.structure().borrow_file("textures/sword.png").unwrap().is_loaded());
+        assert_eq!(lazy.borrow_file("missing"), Err(DirError::NoFile("missing".to_owned())));
+        assert_eq!(lazy.structure().read_link("latest").unwrap(), TreePath::new("scripts"));
+        assert_eq!(lazy.borrow_file("latest/init.lua").unwrap(), b"print('hi')");
+
+        lazy.borrow_file_mut("textures/sword.png").unwrap().push(8);
+        let restored = lazy.into_tree().unwrap();
+        assert_eq!(restored.borrow_file("textures/sword.png").unwrap().len(), 65);
+        tree.borrow_file_mut("textures/sword.png").unwrap().push(8);
+        assert_eq!(restored, tree);
+        restored.validate().unwrap();
+
+        for paths in [["a", "a/b"], ["a/b", "a"]] {
+            let mut writer = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+            for path in paths {
+                writer.start_file(path, zip::write::SimpleFileOptions::default()).unwrap();
+            }
+            let archive = writer.finish().unwrap();
+            assert_eq!(ZipTree::new("mod", archive).err(), Some(DirError::AmbiguousPath("a".to_owned())));
+        }
+    }
+
+    #[test]
+    fn flatten () {
+        let mut tree: PathTree<usize> = [("a/b/c", 1), ("a/d", 2), ("e", 3)].into_iter().collect();
+        tree.create_dir("empty").unwrap();
+        let flat = tree.clone().flatten();
+        assert_eq!(flat.iter().map(|(path, file)| (path.as_str(), *file)).collect::<Vec<_>>(), [("a/b/c", 1), ("a/d", 2), ("e", 3)]);
+
+        let mut restored = PathTree::unflatten(tree.get_name().as_str(), flat).unwrap();
+        restored.validate().unwrap();
+        assert!(restored.borrow_dir("empty").is_err());
+        restored.create_dir("empty").unwrap();
+        assert_eq!(restored, tree);
+
+        let hashed: std::collections::HashMap<String, usize> = tree.flatten().into_iter().collect();
+        let directory = Directory::unflatten(hashed).unwrap();
+        assert_eq!(directory.borrow_dir("a/b").unwrap().get_depth(), 2.0);
+        directory.validate().unwrap();
+
+        assert_eq!(Directory::unflatten([("a/b", 1), ("a", 2)]), Err(DirError::AmbiguousPath("a".to_owned())));
+        assert_eq!(Directory::unflatten([("a", 1), ("a/b/c", 2)]), Err(DirError::AmbiguousPath("a".to_owned())));
+        assert_eq!(Directory::unflatten([("x/a/b", 1), ("x/a/b/c", 2)]), Err(DirError::AmbiguousPath("x/a/b".to_owned())));
+        assert_eq!(Directory::unflatten([("a//b", 1), ("a/./b", 2)]), Err(DirError::NameInUse("a/b".to_owned())));
+        assert_eq!(Directory::unflatten([("", 1)]), Err(DirError::InvalidPath("".to_owned())));
+    }
+}
@@ -0,0 +1,377 @@
+use std::collections::VecDeque;
+
+use crate::{DirError, DirFile, DirFiles, DirHierarchy, DirMapMulti, DirMapSingle, DirMulti, DirSingle, TreePath, TreePathBuf};
+use crate::{Component as PathComponent, DirStorage};
+use crate::tree::{join_names, resolve_parent, resolve_path, validate_name};
+use crate::storage::storage_remove;
+
+// #============================#
+// #=== LINK IMPLEMENTATIONS ===#
+
+/// Maximum number of links followed while resolving a single path, exceeding it fails with [`DirError::LinkLoop`]
+pub const MAX_LINK_HOPS: usize = 40;
+
+/// Borrow resolved names as string slices
+fn as_names(names: &[String]) -> Vec<&str> {
+    names.iter().map(String::as_str).collect()
+}
+
+/// Directory that can hold links, shared by the link resolution of both directory types
+pub(crate) trait LinkNode: DirHierarchy<Self> + Sized {
+    /// Borrow links stored directly in this directory
+    fn links(&self) -> &DirStorage<TreePathBuf>;
+}
+impl <T> LinkNode for DirMulti<T> {
+    fn links(&self) -> &DirStorage<TreePathBuf> {
+        &self.link
+    }
+}
+impl <T> LinkNode for DirSingle<T> {
+    fn links(&self) -> &DirStorage<TreePathBuf> {
+        &self.link
+    }
+}
+
+
+/// # Resolver
+/// Walks names from a directory while following links. Every link is resolved from the directory holding it,
+/// so the directories already visited are kept on a stack instead of being looked up from the start again.
+struct Resolver<'a, D> {
+    base: &'a str,
+    stack: Vec<&'a D>,
+    resolved: Vec<String>,
+    hops: usize,
+}
+impl <'a, D: LinkNode> Resolver<'a, D> {
+    fn new(root: &'a D) -> Self {
+        Resolver { base: root.get_path(), stack: vec![root], resolved: Vec::new(), hops: 0 }
+    }
+
+    /// Borrow directory the resolver currently stands in
+    fn current(&self) -> &'a D {
+        self.stack[self.stack.len() - 1]
+    }
+
+    /// Count a followed link, failing once there were too many of them
+    fn hop(&mut self, name: &str) -> Result<(), DirError> {
+        self.hops += 1;
+        match self.hops > MAX_LINK_HOPS {
+            true => Err(DirError::LinkLoop(join_names(&as_names(&self.resolved), name))),
+            false => Ok(()),
+        }
+    }
+
+    /// Queue names of the link target in front of the remaining names, an absolute target starts over from the root
+    fn redirect(&mut self, target: &TreePath, remaining: &mut VecDeque<String>) -> Result<(), DirError> {
+        let names: Vec<String> = match target.is_absolute() {
+            true => {
+                self.stack.truncate(1);
+                self.resolved.clear();
+                resolve_path(self.base, target)?.into_iter().map(str::to_owned).collect()
+            },
+            false => TreePathBuf::new(target).components().filter_map(|component| match component {
+                PathComponent::Normal(name) => Some(name.to_owned()),
+                PathComponent::ParentDir => Some("..".to_owned()),
+                PathComponent::RootDir => None,
+            }).collect(),
+        };
+        for name in names.into_iter().rev() {
+            remaining.push_front(name);
+        }
+        Ok(())
+    }
+
+    /// Step to the parent directory. Above the starting directory the rest is resolved from the root like any other path,
+    /// so it has to lead back inside.
+    fn leave(&mut self, remaining: &mut VecDeque<String>) -> Result<(), DirError> {
+        if self.resolved.pop().is_some() {
+            self.stack.pop();
+        } else if !self.base.is_empty() {
+            let rest: Vec<String> = remaining.drain(..).collect();
+            let path = format!("/{}/../{}", self.base, rest.join("/"));
+            let names = resolve_path(self.base, TreePath::new(&path))?;
+            remaining.extend(names.into_iter().map(str::to_owned));
+        }
+        Ok(())
+    }
+
+    /// Walk the names and record names of the real directories on the way, the last name is followed only if `follow` is set
+    fn walk(&mut self, names: impl IntoIterator<Item = String>, follow: bool) -> Result<(), DirError> {
+        let mut remaining: VecDeque<String> = names.into_iter().collect();
+        while let Some(name) = remaining.pop_front() {
+            if name == ".." { self.leave(&mut remaining)?; continue }
+            let directory = self.current();
+            if let Ok(subdirectory) = directory.obtain_dir(name.as_str()) {
+                self.stack.push(subdirectory);
+                self.resolved.push(name);
+                continue;
+            }
+            let last = remaining.is_empty();
+            match directory.links().get(&name) {
+                Some(target) if follow || !last => {
+                    self.hop(&name)?;
+                    self.redirect(target, &mut remaining)?;
+                },
+                _ if last => self.resolved.push(name),
+                _ => return Err(DirError::NoDir(name)),
+            }
+        }
+        Ok(())
+    }
+}
+
+impl <T> DirMulti<T> {
+    /// Resolve names into names of real directories by following links on the way, the last name is followed only if `follow` is set
+    pub(crate) fn follow_links(&self, names: &[&str], follow: bool) -> Result<Vec<String>, DirError> {
+        let mut resolver = Resolver::new(self);
+        resolver.walk(names.iter().map(|name| (*name).to_owned()), follow)?;
+        Ok(resolver.resolved)
+    }
+
+    /// Resolve names of a parent directory and a file name into a real location by following links, the file name is followed only if `follow` is set
+    pub(crate) fn follow_file_links(&self, names: &[&str], name: &str, follow: bool) -> Result<(Vec<String>, String), DirError> {
+        let mut resolver = Resolver::new(self);
+        resolver.walk(names.iter().map(|name| (*name).to_owned()), true)?;
+        let mut name = name.to_owned();
+        loop {
+            if resolver.stack.len() != resolver.resolved.len() + 1 {
+                return Err(DirError::NoDir(resolver.resolved.pop().unwrap_or_default()));
+            }
+            let directory = resolver.current();
+            let target = match follow && !directory.file.contains_key(&name) {
+                true => directory.link.get(&name),
+                false => None,
+            };
+            match target {
+                Some(target) => {
+                    resolver.hop(&name)?;
+                    let target = TreePathBuf::new(target);
+                    let (parent, file_name) = match target.file_name() {
+                        Some(file_name) => (target.parent().unwrap_or(TreePath::new("")), file_name),
+                        None => return Err(DirError::InvalidPath(target.to_string())),
+                    };
+                    let mut remaining = VecDeque::new();
+                    resolver.redirect(parent, &mut remaining)?;
+                    resolver.walk(remaining, true)?;
+                    name = file_name.to_owned();
+                },
+                None => return Ok((resolver.resolved, name)),
+            }
+        }
+    }
+
+    /// Borrow directory located at the resolved names, following links on the way
+    pub(crate) fn lookup_dir(&self, names: &[&str]) -> Result<&DirMulti<T>, DirError> {
+        match self.descend(names) {
+            Ok(directory) => Ok(directory),
+            Err(_) => self.descend(&as_names(&self.follow_links(names, true)?)),
+        }
+    }
+
+    /// Mutably borrow directory located at the resolved names, following links on the way
+    pub(crate) fn lookup_dir_mut(&mut self, names: &[&str]) -> Result<&mut DirMulti<T>, DirError> {
+        if self.descend(names).is_ok() { return self.descend_mut(names) }
+        let names = self.follow_links(names, true)?;
+        self.descend_mut(&as_names(&names))
+    }
+
+    /// Borrow file located at the resolved names, following links on the way
+    pub(crate) fn lookup_file(&self, names: &[&str], name: &str) -> Result<&T, DirError> {
+        if let Ok(file) = self.descend(names).and_then(|directory| directory.obtain_file(name)) { return Ok(file) }
+        let (names, name) = self.follow_file_links(names, name, true)?;
+        self.descend(&as_names(&names))?.obtain_file(name)
+    }
+
+    /// Mutably borrow file located at the resolved names, following links on the way
+    pub(crate) fn lookup_file_mut(&mut self, names: &[&str], name: &str) -> Result<&mut T, DirError> {
+        if self.descend(names).is_ok_and(|directory| directory.file.contains_key(name)) { return self.descend_mut(names)?.obtain_file_mut(name) }
+        let (names, name) = self.follow_file_links(names, name, true)?;
+        self.descend_mut(&as_names(&names))?.obtain_file_mut(name)
+    }
+}
+impl <T> DirSingle<T> {
+    /// Resolve names into names of real directories by following links on the way, the last name is followed only if `follow` is set
+    pub(crate) fn follow_links(&self, names: &[&str], follow: bool) -> Result<Vec<String>, DirError> {
+        let mut resolver = Resolver::new(self);
+        resolver.walk(names.iter().map(|name| (*name).to_owned()), follow)?;
+        Ok(resolver.resolved)
+    }
+
+    /// Borrow directory located at the resolved names, following links on the way
+    pub(crate) fn lookup_dir(&self, names: &[&str]) -> Result<&DirSingle<T>, DirError> {
+        match self.descend(names) {
+            Ok(directory) => Ok(directory),
+            Err(_) => self.descend(&as_names(&self.follow_links(names, true)?)),
+        }
+    }
+
+    /// Mutably borrow directory located at the resolved names, following links on the way
+    pub(crate) fn lookup_dir_mut(&mut self, names: &[&str]) -> Result<&mut DirSingle<T>, DirError> {
+        if self.descend(names).is_ok() { return self.descend_mut(names) }
+        let names = self.follow_links(names, true)?;
+        self.descend_mut(&as_names(&names))
+    }
+}
+
+
+// #===========================#
+// #=== DIR IMPLEMENTATIONS ===#
+
+impl <T> DirMulti<T> {
+    /// Create link at the path pointing at another location of the tree, returns path of the link.
+    /// Relative targets are resolved from the directory holding the link and the target doesn't have to exist yet.
+    ///
+    /// Links are followed by the `borrow_*` methods. A name can't be used by a link and a directory or file at the same time.
+    /// Other operations like insert, remove or move act on the real entries and traversals like iterators, glob and find don't follow links.
+    /// Merge, diff and prune treat a link as an entry of its own, compared by its target.
+    /// ```
+    /// # use pathio::prelude::*;
+    /// let mut tree: PathTree<&str> = PathTree::new("Root");
+    /// tree.insert_file_with_parents("packages/v1.2.3/manifest", "1.2.3").unwrap();
+    /// tree.create_link("packages/latest", "v1.2.3").unwrap();
+    /// assert_eq!(*tree.borrow_file("packages/latest/manifest").unwrap(), "1.2.3");
+    /// assert_eq!(tree.read_link("packages/latest").unwrap(), TreePath::new("v1.2.3"));
+    /// ```
+    pub fn create_link(&mut self, path: impl AsRef<TreePath>, target: impl AsRef<TreePath>) -> Result<String, DirError> {
+        let (names, name) = resolve_parent(self.get_path(), path.as_ref())?;
+        validate_name(name, path.as_ref().as_str())?;
+        let names = self.follow_links(&names, true)?;
+        let link_path = join_names(&as_names(&names), name);
+        let directory = self.descend_mut(&as_names(&names))?;
+        if directory.directory.contains_key(name) || directory.file.contains_key(name) || directory.link.contains_key(name) { return Err(DirError::NameInUse(link_path)) }
+        directory.link.insert(name.to_owned(), target.as_ref().to_path_buf());
+        Ok(link_path)
+    }
+
+    /// Remove link at the path and return its target, the target itself is left untouched
+    pub fn remove_link(&mut self, path: impl AsRef<TreePath>) -> Result<TreePathBuf, DirError> {
+        let (names, name) = resolve_parent(self.get_path(), path.as_ref())?;
+        let names = self.follow_links(&names, true)?;
+        match storage_remove(&mut self.descend_mut(&as_names(&names))?.link, name) {
+            Some(target) => Ok(target),
+            None => Err(DirError::NoLink(path.as_ref().to_string())),
+        }
+    }
+
+    /// Borrow target of the link at the path, the link itself is not followed
+    pub fn read_link(&self, path: impl AsRef<TreePath>) -> Result<&TreePath, DirError> {
+        let (names, name) = resolve_parent(self.get_path(), path.as_ref())?;
+        let names = self.follow_links(&names, true)?;
+        match self.descend(&as_names(&names))?.link.get(name) {
+            Some(target) => Ok(target.as_path()),
+            None => Err(DirError::NoLink(path.as_ref().to_string())),
+        }
+    }
+
+    /// Borrow directory at the path like [`DirHierarchy::borrow_dir`], but without following a link in place of the last name
+    pub fn borrow_dir_nofollow(&self, path: impl AsRef<TreePath>) -> Result<&DirMulti<T>, DirError> {
+        let names = resolve_path(self.get_path(), path.as_ref())?;
+        self.descend(&as_names(&self.follow_links(&names, false)?))
+    }
+
+    /// Borrow file at the path like [`DirFiles::borrow_file`], but without following a link in place of the file name
+    pub fn borrow_file_nofollow(&self, path: impl AsRef<TreePath>) -> Result<&T, DirError> {
+        let (names, name) = resolve_parent(self.get_path(), path.as_ref())?;
+        let (names, name) = self.follow_file_links(&names, name, false)?;
+        self.descend(&as_names(&names))?.obtain_file(name)
+    }
+}
+impl <T> DirSingle<T> {
+    /// Create link at the path pointing at another location of the tree, returns path of the link.
+    /// Relative targets are resolved from the directory holding the link and the target doesn't have to exist yet.
+    ///
+    /// A file is always stored in a directory at its path, so a link can't use the name of an existing directory or file.
+    pub fn create_link(&mut self, path: impl AsRef<TreePath>, target: impl AsRef<TreePath>) -> Result<String, DirError> {
+        let (names, name) = resolve_parent(self.get_path(), path.as_ref())?;
+        validate_name(name, path.as_ref().as_str())?;
+        let names = self.follow_links(&names, true)?;
+        let link_path = join_names(&as_names(&names), name);
+        let directory = self.descend_mut(&as_names(&names))?;
+        if directory.directory.contains_key(name) || directory.link.contains_key(name) { return Err(DirError::NameInUse(link_path)) }
+        directory.link.insert(name.to_owned(), target.as_ref().to_path_buf());
+        Ok(link_path)
+    }
+
+    /// Remove link at the path and return its target, the target itself is left untouched
+    pub fn remove_link(&mut self, path: impl AsRef<TreePath>) -> Result<TreePathBuf, DirError> {
+        let (names, name) = resolve_parent(self.get_path(), path.as_ref())?;
+        let names = self.follow_links(&names, true)?;
+        match storage_remove(&mut self.descend_mut(&as_names(&names))?.link, name) {
+            Some(target) => Ok(target),
+            None => Err(DirError::NoLink(path.as_ref().to_string())),
+        }
+    }
+
+    /// Borrow target of the link at the path, the link itself is not followed
+    pub fn read_link(&self, path: impl AsRef<TreePath>) -> Result<&TreePath, DirError> {
+        let (names, name) = resolve_parent(self.get_path(), path.as_ref())?;
+        let names = self.follow_links(&names, true)?;
+        match self.descend(&as_names(&names))?.link.get(name) {
+            Some(target) => Ok(target.as_path()),
+            None => Err(DirError::NoLink(path.as_ref().to_string())),
+        }
+    }
+
+    /// Borrow directory at the path like [`DirHierarchy::borrow_dir`], but without following a link in place of the last name
+    pub fn borrow_dir_nofollow(&self, path: impl AsRef<TreePath>) -> Result<&DirSingle<T>, DirError> {
+        let names = resolve_path(self.get_path(), path.as_ref())?;
+        self.descend(&as_names(&self.follow_links(&names, false)?))
+    }
+
+    /// Borrow file at the path like [`DirFile::borrow_file`], but without following a link in place of the last name
+    pub fn borrow_file_nofollow(&self, path: impl AsRef<TreePath>) -> Result<Option<&T>, DirError> {
+        Ok(self.borrow_dir_nofollow(path)?.obtain_file())
+    }
+}
+impl <T> DirMapMulti<T> {
+    /// Create link at the path pointing at another location of the tree, see [`DirMulti::create_link`]
+    pub fn create_link(&mut self, path: impl AsRef<TreePath>, target: impl AsRef<TreePath>) -> Result<String, DirError> {
+        self.directory.create_link(path, target)
+    }
+
+    /// Remove link at the path and return its target, the target itself is left untouched
+    pub fn remove_link(&mut self, path: impl AsRef<TreePath>) -> Result<TreePathBuf, DirError> {
+        self.directory.remove_link(path)
+    }
+
+    /// Borrow target of the link at the path, the link itself is not followed
+    pub fn read_link(&self, path: impl AsRef<TreePath>) -> Result<&TreePath, DirError> {
+        self.directory.read_link(path)
+    }
+
+    /// Borrow directory at the path without following a link in place of the last name
+    pub fn borrow_dir_nofollow(&self, path: impl AsRef<TreePath>) -> Result<&DirMulti<T>, DirError> {
+        self.directory.borrow_dir_nofollow(path)
+    }
+
+    /// Borrow file at the path without following a link in place of the file name
+    pub fn borrow_file_nofollow(&self, path: impl AsRef<TreePath>) -> Result<&T, DirError> {
+        self.directory.borrow_file_nofollow(path)
+    }
+}
+impl <T> DirMapSingle<T> {
+    /// Create link at the path pointing at another location of the tree, see [`DirSingle::create_link`]
+    pub fn create_link(&mut self, path: impl AsRef<TreePath>, target: impl AsRef<TreePath>) -> Result<String, DirError> {
+        self.directory.create_link(path, target)
+    }
+
+    /// Remove link at the path and return its target, the target itself is left untouched
+    pub fn remove_link(&mut self, path: impl AsRef<TreePath>) -> Result<TreePathBuf, DirError> {
+        self.directory.remove_link(path)
+    }
+
+    /// Borrow target of the link at the path, the link itself is not followed
+    pub fn read_link(&self, path: impl AsRef<TreePath>) -> Result<&TreePath, DirError> {
+        self.directory.read_link(path)
+    }
+
+    /// Borrow directory at the path without following a link in place of the last name
+    pub fn borrow_dir_nofollow(&self, path: impl AsRef<TreePath>) -> Result<&DirSingle<T>, DirError> {
+        self.directory.borrow_dir_nofollow(path)
+    }
+
+    /// Borrow file at the path without following a link in place of the last name
+    pub fn borrow_file_nofollow(&self, path: impl AsRef<TreePath>) -> Result<Option<&T>, DirError> {
+        self.directory.borrow_file_nofollow(path)
+    }
+}
@@ -0,0 +1,245 @@
+use crate::{DirError, DirHierarchy, DirMapMulti, DirMapSingle, DirMulti, DirSingle, DirStorage, TreePathBuf};
+use crate::tree::join_path;
+
+// #=============================#
+// #=== MERGE IMPLEMENTATIONS ===#
+
+/// Boxed closure combining two conflicting files used by [`MergePolicy`]
+type Combine<'a, T> = Box<dyn FnMut(&str, &mut T, T) + 'a>;
+
+/// ## Merge policy
+/// Decides what happens when both merged trees hold a file at the same path. Directories are always merged recursively.
+///
+/// Links present on both sides with different targets are resolved the same way, except [`MergePolicy::Combine`] which keeps the existing link.
+/// A link using the name of a directory or file on the other side can't be resolved and always fails with [`DirError::DuplicateName`].
+pub enum MergePolicy<'a, T> {
+    /// Keep the file already in the tree
+    KeepLeft,
+
+    /// Replace the file with the one being merged in
+    KeepRight,
+
+    /// Combine the files with the closure, called with `(path, &mut left, right)`
+    Combine (Combine<'a, T>),
+
+    /// Abort with [`DirError::DuplicateName`] before anything is changed
+    Error,
+}
+impl <'a, T> MergePolicy<'a, T> {
+    /// Combine conflicting files with the closure, called with `(path, &mut left, right)`
+    pub fn combine(f: impl FnMut(&str, &mut T, T) + 'a) -> Self {
+        MergePolicy::Combine(Box::new(f))
+    }
+
+    /// Resolve conflict of two links, returns the decision made
+    fn resolve_link(&self, path: &str, left: &mut TreePathBuf, right: TreePathBuf) -> Result<MergeDecision, DirError> {
+        match self {
+            MergePolicy::KeepLeft | MergePolicy::Combine(_) => Ok(MergeDecision::KeptLeft),
+            MergePolicy::KeepRight => {
+                *left = right;
+                Ok(MergeDecision::KeptRight)
+            },
+            MergePolicy::Error => Err(DirError::DuplicateName(path.to_owned())),
+        }
+    }
+
+    /// Resolve the conflict, returns the decision made
+    fn resolve(&mut self, path: &str, left: &mut T, right: T) -> Result<MergeDecision, DirError> {
+        match self {
+            MergePolicy::KeepLeft => Ok(MergeDecision::KeptLeft),
+            MergePolicy::KeepRight => {
+                *left = right;
+                Ok(MergeDecision::KeptRight)
+            },
+            MergePolicy::Combine(f) => {
+                f(path, left, right);
+                Ok(MergeDecision::Combined)
+            },
+            MergePolicy::Error => Err(DirError::DuplicateName(path.to_owned())),
+        }
+    }
+}
+
+/// ## Merge decision
+/// How a single file conflict was resolved.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MergeDecision {
+    /// File already in the tree was kept
+    KeptLeft,
+
+    /// File being merged in replaced the existing one
+    KeptRight,
+
+    /// Files were combined by the closure
+    Combined,
+}
+
+/// ## Merge conflict
+/// File or link present on both sides of a merge.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MergeConflict {
+    /// Path of the file or link relative to the directory merged into
+    pub path: String,
+
+    /// How the conflict was resolved
+    pub decision: MergeDecision,
+}
+
+/// ## Merge report
+/// Every conflict encountered by [`DirMulti::merge_with`] or [`DirSingle::merge_with`] together with the decision made.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MergeReport {
+    /// Conflicts in the order they were resolved
+    pub conflicts: Vec<MergeConflict>,
+}
+impl MergeReport {
+    /// Returns true if no file or link was present on both sides
+    pub fn is_clean(&self) -> bool {
+        self.conflicts.is_empty()
+    }
+}
+
+/// Move links of the other directory into self, links pointing at the same target on both sides are not a conflict
+fn merge_links<T>(links: &mut DirStorage<TreePathBuf>, other: DirStorage<TreePathBuf>, path: &str, policy: &mut MergePolicy<'_, T>, report: &mut MergeReport) -> Result<(), DirError> {
+    for (name, target) in other {
+        match links.get_mut(&name) {
+            Some(existing) if *existing == target => {},
+            Some(existing) => {
+                let link_path = join_path(path, &name);
+                let decision = policy.resolve_link(&link_path, existing, target)?;
+                report.conflicts.push(MergeConflict { path: link_path, decision });
+            },
+            None => { links.insert(name, target); },
+        }
+    }
+    Ok(())
+}
+
+
+// #===========================#
+// #=== DIR IMPLEMENTATIONS ===#
+
+impl <T> DirMulti<T> {
+    /// Deep merge the directory into self. Subdirectories present on both sides are merged recursively,
+    /// files and links present on both sides are resolved by the policy and listed in the returned report.
+    /// ```
+    /// # use pathio::prelude::*;
+    /// let mut base: PathTree<u32> = [("config/volume", 5), ("config/speed", 1)].into_iter().collect();
+    /// let overlay: PathTree<u32> = [("config/volume", 7), ("config/extra/new", 2)].into_iter().collect();
+    ///
+    /// let report = base.merge_with(overlay, MergePolicy::KeepRight).unwrap();
+    /// assert_eq!(report.conflicts.len(), 1);
+    /// assert_eq!(*base.borrow_file("config/volume").unwrap(), 7);
+    /// assert_eq!(*base.borrow_file("config/extra/new").unwrap(), 2);
+    /// ```
+    pub fn merge_with(&mut self, directory: impl Into<DirMulti<T>>, mut policy: MergePolicy<'_, T>) -> Result<MergeReport, DirError> {
+        let directory = directory.into();
+        let strict = matches!(policy, MergePolicy::Error);
+        if let Some(path) = self.find_conflict(&directory, "", strict) { return Err(DirError::DuplicateName(path)) }
+        let mut report = MergeReport::default();
+        self.merge_at(directory, "", &mut policy, &mut report)?;
+        Ok(report)
+    }
+
+    /// Returns path of the first link clashing with a directory or file of the other side.
+    /// If `strict` is set, files and differing links present in both directories are reported too.
+    fn find_conflict(&self, other: &DirMulti<T>, path: &str, strict: bool) -> Option<String> {
+        for name in other.file.keys() {
+            if self.link.contains_key(name) || (strict && self.file.contains_key(name)) { return Some(join_path(path, name)) }
+        }
+        for (name, target) in &other.link {
+            if self.file.contains_key(name) || self.directory.contains_key(name) { return Some(join_path(path, name)) }
+            if strict && self.link.get(name).is_some_and(|existing| existing != target) { return Some(join_path(path, name)) }
+        }
+        for (name, subdirectory) in &other.directory {
+            if self.link.contains_key(name) { return Some(join_path(path, name)) }
+            if let Some(directory) = self.directory.get(name) {
+                if let Some(conflict) = directory.find_conflict(subdirectory, &join_path(path, name), strict) { return Some(conflict) }
+            }
+        }
+        None
+    }
+
+    /// Recursively move content of the directory into self
+    fn merge_at(&mut self, other: DirMulti<T>, path: &str, policy: &mut MergePolicy<'_, T>, report: &mut MergeReport) -> Result<(), DirError> {
+        for (name, file) in other.file {
+            match self.file.get_mut(&name) {
+                Some(existing) => {
+                    let file_path = join_path(path, &name);
+                    let decision = policy.resolve(&file_path, existing, file)?;
+                    report.conflicts.push(MergeConflict { path: file_path, decision });
+                },
+                None => { self.file.insert(name, file); },
+            }
+        }
+        merge_links(&mut self.link, other.link, path, policy, report)?;
+        for (name, subdirectory) in other.directory {
+            match self.directory.get_mut(&name) {
+                Some(directory) => directory.merge_at(subdirectory, &join_path(path, &name), policy, report)?,
+                None => { self.add_dir(name, subdirectory)?; },
+            }
+        }
+        Ok(())
+    }
+}
+impl <T> DirSingle<T> {
+    /// Deep merge the directory into self. Subdirectories present on both sides are merged recursively,
+    /// directories holding a file and links present on both sides are resolved by the policy and listed in the returned report.
+    pub fn merge_with(&mut self, directory: impl Into<DirSingle<T>>, mut policy: MergePolicy<'_, T>) -> Result<MergeReport, DirError> {
+        let directory = directory.into();
+        let strict = matches!(policy, MergePolicy::Error);
+        if let Some(path) = self.find_conflict(&directory, "", strict) { return Err(DirError::DuplicateName(path)) }
+        let mut report = MergeReport::default();
+        self.merge_at(directory, "", &mut policy, &mut report)?;
+        Ok(report)
+    }
+
+    /// Returns path of the first link clashing with a directory of the other side.
+    /// If `strict` is set, directories holding a file and differing links present in both trees are reported too.
+    fn find_conflict(&self, other: &DirSingle<T>, path: &str, strict: bool) -> Option<String> {
+        if strict && self.file.is_some() && other.file.is_some() { return Some(path.to_owned()) }
+        for (name, target) in &other.link {
+            if self.directory.contains_key(name) { return Some(join_path(path, name)) }
+            if strict && self.link.get(name).is_some_and(|existing| existing != target) { return Some(join_path(path, name)) }
+        }
+        for (name, subdirectory) in &other.directory {
+            if self.link.contains_key(name) { return Some(join_path(path, name)) }
+            if let Some(directory) = self.directory.get(name) {
+                if let Some(conflict) = directory.find_conflict(subdirectory, &join_path(path, name), strict) { return Some(conflict) }
+            }
+        }
+        None
+    }
+
+    /// Recursively move content of the directory into self
+    fn merge_at(&mut self, other: DirSingle<T>, path: &str, policy: &mut MergePolicy<'_, T>, report: &mut MergeReport) -> Result<(), DirError> {
+        match (&mut self.file, other.file) {
+            (Some(existing), Some(file)) => {
+                let decision = policy.resolve(path, existing, file)?;
+                report.conflicts.push(MergeConflict { path: path.to_owned(), decision });
+            },
+            (None, Some(file)) => self.file = Some(file),
+            (_, None) => {},
+        }
+        merge_links(&mut self.link, other.link, path, policy, report)?;
+        for (name, subdirectory) in other.directory {
+            match self.directory.get_mut(&name) {
+                Some(directory) => directory.merge_at(subdirectory, &join_path(path, &name), policy, report)?,
+                None => { self.add_dir(name, subdirectory)?; },
+            }
+        }
+        Ok(())
+    }
+}
+impl <T> DirMapMulti<T> {
+    /// Deep merge the DirMap or Dir into self, see [`DirMulti::merge_with`]
+    pub fn merge_with(&mut self, directory: impl Into<DirMulti<T>>, policy: MergePolicy<'_, T>) -> Result<MergeReport, DirError> {
+        self.directory.merge_with(directory, policy)
+    }
+}
+impl <T> DirMapSingle<T> {
+    /// Deep merge the DirMap or Dir into self, see [`DirSingle::merge_with`]
+    pub fn merge_with(&mut self, directory: impl Into<DirSingle<T>>, policy: MergePolicy<'_, T>) -> Result<MergeReport, DirError> {
+        self.directory.merge_with(directory, policy)
+    }
+}
@@ -0,0 +1,359 @@
+use std::borrow::Borrow;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::ops::Deref;
+
+use crate::DirError;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+// #============================#
+// #=== PATH IMPLEMENTATIONS ===#
+
+/// ## Path component
+/// Single meaningful part of a [`TreePath`]. Empty and `.` segments are not components.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Component<'a> {
+    /// Leading `/` of an absolute path, refers to the DirMap root
+    RootDir,
+
+    /// `..` referring to the parent directory
+    ParentDir,
+
+    /// Name of a directory or file
+    Normal (&'a str),
+}
+impl <'a> Component<'a> {
+    /// Returns the component as it is written in a path
+    pub fn as_str(&self) -> &'a str {
+        match self {
+            Component::RootDir => "/",
+            Component::ParentDir => "..",
+            Component::Normal(name) => name,
+        }
+    }
+}
+
+
+/// ## Tree path
+/// Borrowed path inside of a directory tree, the `str` counterpart of [`TreePathBuf`] just like [`std::path::Path`].
+/// Segments are separated by `/`, a leading `/` starts at the DirMap root and `..` refers to the parent directory.
+/// Any `&str` or `String` can be used where a `TreePath` is expected.
+///
+/// Paths are compared and hashed by their normalised form, so `a/../b` equals `b` just like the matching [`TreePathBuf`].
+/// ```
+/// # use pathio::prelude::*;
+/// let path = TreePath::new("a/./b//file");
+/// assert_eq!(path.file_name(), Some("file"));
+/// assert_eq!(path.parent().unwrap(), TreePath::new("a/b"));
+/// assert_eq!(path.join("../c"), TreePathBuf::from("a/b/c"));
+/// assert_eq!(TreePath::new("a/../b"), TreePath::new("b"));
+/// ```
+#[derive(Debug, Eq)]
+#[repr(transparent)]
+pub struct TreePath {
+    inner: str,
+}
+impl TreePath {
+    /// Wraps string slice as a path, no allocation is made
+    pub fn new(path: &str) -> &TreePath {
+        // SAFETY: TreePath is a transparent wrapper around str
+        unsafe { &*(path as *const str as *const TreePath) }
+    }
+
+    /// Returns the path as it was written
+    pub fn as_str(&self) -> &str {
+        &self.inner
+    }
+
+    /// Returns true if the path starts at the DirMap root
+    pub fn is_absolute(&self) -> bool {
+        self.inner.starts_with('/')
+    }
+
+    /// Returns true if the path refers to the directory it is resolved against
+    pub fn is_empty(&self) -> bool {
+        self.components().next().is_none()
+    }
+
+    /// Iterate over the components of the path, skipping empty and `.` segments
+    pub fn components(&self) -> impl Iterator<Item = Component<'_>> {
+        self.segments().map(|(_, component)| component)
+    }
+
+    /// Components of the path together with their byte offset in the string
+    fn segments(&self) -> impl Iterator<Item = (usize, Component<'_>)> {
+        let absolute = self.is_absolute();
+        let mut offset = 0;
+        self.inner.split('/').enumerate().filter_map(move |(i, name)| {
+            let start = offset;
+            offset += name.len() + 1;
+            match name {
+                "" if i == 0 && absolute => Some((start, Component::RootDir)),
+                "" | "." => None,
+                ".." => Some((start, Component::ParentDir)),
+                _ => Some((start, Component::Normal(name))),
+            }
+        })
+    }
+
+    /// Split the path into its normalised form: whether it is absolute, number of leading `..` and the remaining names
+    fn normal_form(&self) -> (bool, usize, Vec<&str>) {
+        let mut names: Vec<&str> = Vec::new();
+        let mut parents = 0;
+        for component in self.components() {
+            match component {
+                Component::RootDir => {},
+                Component::ParentDir => if names.pop().is_none() { parents += 1 },
+                Component::Normal(name) => names.push(name),
+            }
+        }
+        if self.is_absolute() { parents = 0 }
+        (self.is_absolute(), parents, names)
+    }
+
+    /// Returns the path without its last component, or none if there is no name to drop
+    pub fn parent(&self) -> Option<&TreePath> {
+        let (start, component) = self.segments().last()?;
+        match component {
+            Component::Normal(_) | Component::ParentDir => {
+                let parent = self.inner[..start].trim_end_matches('/');
+                if parent.is_empty() && self.is_absolute() { Some(TreePath::new("/")) } else { Some(TreePath::new(parent)) }
+            },
+            Component::RootDir => None,
+        }
+    }
+
+    /// Returns the last name in the path, or none if the path ends with `..` or is the root
+    pub fn file_name(&self) -> Option<&str> {
+        match self.components().last()? {
+            Component::Normal(name) => Some(name),
+            _ => None,
+        }
+    }
+
+    /// Returns the remainder of the path after the given leading components
+    pub fn strip_prefix(&self, base: impl AsRef<TreePath>) -> Result<&TreePath, DirError> {
+        let mut prefix = base.as_ref().components();
+        for (start, component) in self.segments() {
+            match prefix.next() {
+                Some(expected) if expected == component => {},
+                Some(_) => return Err(DirError::InvalidPath(self.inner.to_owned())),
+                None => return Ok(TreePath::new(&self.inner[start..])),
+            }
+        }
+        match prefix.next() {
+            Some(_) => Err(DirError::InvalidPath(self.inner.to_owned())),
+            None => Ok(TreePath::new("")),
+        }
+    }
+
+    /// Creates owned path with the given path appended, an absolute path replaces self
+    pub fn join(&self, path: impl AsRef<TreePath>) -> TreePathBuf {
+        let mut buf = self.to_path_buf();
+        buf.push(path);
+        buf
+    }
+
+    /// Creates owned normalised copy of the path
+    pub fn to_path_buf(&self) -> TreePathBuf {
+        TreePathBuf::from(self)
+    }
+}
+impl PartialEq for TreePath {
+    fn eq(&self, other: &Self) -> bool {
+        self.normal_form() == other.normal_form()
+    }
+}
+impl Hash for TreePath {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.normal_form().hash(state)
+    }
+}
+impl fmt::Display for TreePath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.inner)
+    }
+}
+impl ToOwned for TreePath {
+    type Owned = TreePathBuf;
+    fn to_owned(&self) -> TreePathBuf {
+        self.to_path_buf()
+    }
+}
+impl AsRef<TreePath> for TreePath {
+    fn as_ref(&self) -> &TreePath {
+        self
+    }
+}
+impl AsRef<TreePath> for str {
+    fn as_ref(&self) -> &TreePath {
+        TreePath::new(self)
+    }
+}
+impl AsRef<TreePath> for String {
+    fn as_ref(&self) -> &TreePath {
+        TreePath::new(self)
+    }
+}
+impl <'a> From<&'a str> for &'a TreePath {
+    fn from(path: &'a str) -> Self {
+        TreePath::new(path)
+    }
+}
+
+
+/// ## Tree path buffer
+/// Owned path inside of a directory tree, the `String` counterpart of [`TreePath`] just like [`std::path::PathBuf`].
+/// The path is normalised once when constructed: repeated and trailing `/` are collapsed, `.` segments are dropped
+/// and `..` segments cancel out the preceding names. Leading `..` of a relative path are kept, as they depend on
+/// the directory the path is resolved against.
+///
+/// [`TreePathBuf::new`] accepts any string, while [`TreePathBuf::try_new`] also validates that no `..` climbs above
+/// the start of the path, so the path can't leave the directory it is resolved against.
+/// ```
+/// # use pathio::prelude::*;
+/// let path = TreePathBuf::from("a//b/./../c/");
+/// assert_eq!(path.as_str(), "a/c");
+/// assert_eq!(TreePathBuf::from("/../a").as_str(), "/a");
+/// assert_eq!(TreePathBuf::try_new("/../a"), Err(DirError::InvalidPath("/../a".to_owned())));
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct TreePathBuf {
+    inner: String,
+}
+impl TreePathBuf {
+    /// Creates normalised path
+    pub fn new(path: impl AsRef<TreePath>) -> Self {
+        let (absolute, parents, names) = path.as_ref().normal_form();
+        let mut inner = if absolute { String::from("/") } else { "../".repeat(parents) };
+        inner += &names.join("/");
+        if inner.len() > 1 && inner.ends_with('/') { inner.pop(); }
+        TreePathBuf { inner }
+    }
+
+    /// Creates normalised path, failing with [`DirError::InvalidPath`] if a `..` climbs above the start of the path
+    pub fn try_new(path: impl AsRef<TreePath>) -> Result<Self, DirError> {
+        let path = path.as_ref();
+        let mut depth: usize = 0;
+        for component in path.components() {
+            match component {
+                Component::RootDir => {},
+                Component::ParentDir => match depth.checked_sub(1) {
+                    Some(parent) => depth = parent,
+                    None => return Err(DirError::InvalidPath(path.to_string())),
+                },
+                Component::Normal(_) => depth += 1,
+            }
+        }
+        Ok(TreePathBuf::new(path))
+    }
+
+    /// Borrow as [`TreePath`]
+    pub fn as_path(&self) -> &TreePath {
+        TreePath::new(&self.inner)
+    }
+
+    /// Appends path to self, an absolute path replaces self
+    pub fn push(&mut self, path: impl AsRef<TreePath>) {
+        let path = path.as_ref();
+        *self = if path.is_absolute() || self.inner.is_empty() {
+            TreePathBuf::new(path)
+        } else {
+            TreePathBuf::new(format!("{}/{}", self.inner, path))
+        };
+    }
+
+    /// Drops the last component, returns false if there was no name to drop
+    pub fn pop(&mut self) -> bool {
+        match self.as_path().file_name() {
+            Some(_) => {
+                let parent = self.as_path().parent().map(TreePathBuf::new).unwrap_or_default();
+                *self = parent;
+                true
+            },
+            None => false,
+        }
+    }
+
+    /// Consumes self and returns the inner string
+    pub fn into_string(self) -> String {
+        self.inner
+    }
+}
+impl Deref for TreePathBuf {
+    type Target = TreePath;
+    fn deref(&self) -> &TreePath {
+        self.as_path()
+    }
+}
+impl Hash for TreePathBuf {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.as_path().hash(state)
+    }
+}
+impl Borrow<TreePath> for TreePathBuf {
+    fn borrow(&self) -> &TreePath {
+        self.as_path()
+    }
+}
+impl AsRef<TreePath> for TreePathBuf {
+    fn as_ref(&self) -> &TreePath {
+        self.as_path()
+    }
+}
+impl fmt::Display for TreePathBuf {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.inner)
+    }
+}
+impl From<&TreePath> for TreePathBuf {
+    fn from(path: &TreePath) -> Self {
+        TreePathBuf::new(path)
+    }
+}
+impl From<&str> for TreePathBuf {
+    fn from(path: &str) -> Self {
+        TreePathBuf::new(path)
+    }
+}
+impl From<String> for TreePathBuf {
+    fn from(path: String) -> Self {
+        TreePathBuf::new(path)
+    }
+}
+impl From<TreePathBuf> for String {
+    fn from(path: TreePathBuf) -> Self {
+        path.inner
+    }
+}
+impl PartialEq<TreePath> for TreePathBuf {
+    fn eq(&self, other: &TreePath) -> bool {
+        self.as_path() == other
+    }
+}
+impl PartialEq<TreePathBuf> for TreePath {
+    fn eq(&self, other: &TreePathBuf) -> bool {
+        self == other.as_path()
+    }
+}
+#[cfg(feature = "serde")]
+impl Serialize for TreePathBuf {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&self.inner)
+    }
+}
+#[cfg(feature = "serde")]
+impl <'de> Deserialize<'de> for TreePathBuf {
+    /// Deserialized path is normalised just like when constructed
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        String::deserialize(deserializer).map(TreePathBuf::new)
+    }
+}
@@ -0,0 +1,73 @@
+use std::mem;
+
+use crate::{DirMapMulti, DirMulti, DirectoryInit, IntoIter};
+use crate::tree::join_path;
+
+// #==============================#
+// #=== RETAIN IMPLEMENTATIONS ===#
+
+impl <T> DirMulti<T> {
+    /// Keep only the files for which the closure returns true, called with `(path, &mut file)` for every file in the tree
+    pub fn retain_files(&mut self, mut f: impl FnMut(&str, &mut T) -> bool) {
+        self.walk_mut(|path, directory| {
+            directory.file.retain(|name, file| f(&join_path(path, name), file));
+        });
+    }
+
+    /// Keep only the subdirectories for which the closure returns true, called with `(path, &mut directory)` before visiting them.
+    /// Removed directories are dropped together with their whole content.
+    pub fn retain_dirs(&mut self, mut f: impl FnMut(&str, &mut DirMulti<T>) -> bool) {
+        self.walk_mut(|path, directory| {
+            directory.directory.retain(|name, subdirectory| f(&join_path(path, name), subdirectory));
+        });
+    }
+
+    /// Remove all files, subdirectories and links, yielding the files as owned `(path, file)` pairs. The directory itself stays in place.
+    pub fn drain(&mut self) -> IntoIter<T> {
+        let mut drained = DirMulti::new();
+        drained.file = mem::take(&mut self.file);
+        drained.directory = mem::take(&mut self.directory);
+        self.link.clear();
+        drained.into_iter()
+    }
+
+    /// Remove every subdirectory that holds no files or links in its whole subtree, returns paths of the removed directories
+    pub fn prune_empty(&mut self) -> Vec<String> {
+        let mut removed = Vec::new();
+        self.prune_empty_at("", &mut removed);
+        removed
+    }
+
+    /// Prune subdirectories bottom-up, recording their paths relative to the directory pruning started from
+    fn prune_empty_at(&mut self, path: &str, removed: &mut Vec<String>) {
+        for (name, subdirectory) in self.directory.iter_mut() {
+            subdirectory.prune_empty_at(&join_path(path, name), removed);
+        }
+        self.directory.retain(|name, subdirectory| {
+            let keep = !subdirectory.file.is_empty() || !subdirectory.directory.is_empty() || !subdirectory.link.is_empty();
+            if !keep { removed.push(join_path(path, name)) }
+            keep
+        });
+    }
+}
+impl <T> DirMapMulti<T> {
+    /// Keep only the files for which the closure returns true, called with `(path, &mut file)` for every file in the tree
+    pub fn retain_files(&mut self, f: impl FnMut(&str, &mut T) -> bool) {
+        self.directory.retain_files(f)
+    }
+
+    /// Keep only the directories for which the closure returns true, called with `(path, &mut directory)` before visiting them
+    pub fn retain_dirs(&mut self, f: impl FnMut(&str, &mut DirMulti<T>) -> bool) {
+        self.directory.retain_dirs(f)
+    }
+
+    /// Remove all files, directories and links, yielding the files as owned `(path, file)` pairs
+    pub fn drain(&mut self) -> IntoIter<T> {
+        self.directory.drain()
+    }
+
+    /// Remove every directory that holds no files or links in its whole subtree, returns paths of the removed directories
+    pub fn prune_empty(&mut self) -> Vec<String> {
+        self.directory.prune_empty()
+    }
+}
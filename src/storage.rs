@@ -0,0 +1,31 @@
+// #===============================#
+// #=== STORAGE IMPLEMENTATIONS ===#
+
+#[cfg(feature = "sorted")]
+type Backend<V> = std::collections::BTreeMap<String, V>;
+
+#[cfg(all(feature = "insertion-order", not(feature = "sorted")))]
+type Backend<V> = indexmap::IndexMap<String, V, ahash::RandomState>;
+
+#[cfg(not(any(feature = "sorted", feature = "insertion-order")))]
+type Backend<V> = ahash::AHashMap<String, V>;
+
+/// ## Directory storage
+/// Map holding files and subdirectories of a directory, keyed by their name.
+///
+/// The backend is selected by cargo features, which decides the order of every traversal, rendered tree and serialized output:
+/// * default - `ahash::AHashMap`, fastest, order is random and changes from run to run
+/// * `sorted` - `std::collections::BTreeMap`, entries ordered by name
+/// * `insertion-order` - `indexmap::IndexMap`, entries in the order they were inserted
+///
+/// If both features are enabled, `sorted` takes precedence.
+pub type DirStorage<V> = Backend<V>;
+
+/// Remove entry from the storage, keeping the order of the remaining entries
+pub(crate) fn storage_remove<V>(storage: &mut DirStorage<V>, name: &str) -> Option<V> {
+    #[cfg(all(feature = "insertion-order", not(feature = "sorted")))]
+    { storage.shift_remove(name) }
+
+    #[cfg(not(all(feature = "insertion-order", not(feature = "sorted"))))]
+    { storage.remove(name) }
+}
@@ -0,0 +1,125 @@
+use std::borrow::Borrow;
+use std::io::{self, Read, Write};
+use std::path::Path;
+
+use tar::{Archive, Builder, EntryType, Header};
+
+use crate::{DirError, DirHierarchy, DirMapMulti, DirMulti, PathTreeInit, TreePath};
+use crate::fs::{Planned, io_error, link_target, plan};
+use crate::tree::{join_names, resolve_parent, resolve_path};
+
+// #===========================#
+// #=== TAR IMPLEMENTATIONS ===#
+
+/// Create header of the entry type with the permissions, size and timestamps are left at zero for reproducible archives
+fn header(entry_type: EntryType, mode: u32) -> Header {
+    let mut header = Header::new_gnu();
+    header.set_entry_type(entry_type);
+    header.set_mode(mode);
+    header.set_size(0);
+    header.set_mtime(0);
+    header
+}
+
+impl <T: AsRef<[u8]>> DirMulti<T> {
+    /// Write the directory as a tar stream with paths relative to it, subdirectories become directory entries and files regular file entries.
+    ///
+    /// Links become symlink entries, absolute links pointing outside of the directory are skipped.
+    pub fn write_tar(&self, writer: impl Write) -> Result<(), DirError> {
+        let mut planned = Vec::new();
+        plan(self, self.get_path(), "", 0, true, &mut planned, &mut Vec::new());
+        let mut builder = Builder::new(writer);
+        for (path, entry) in planned {
+            let result = match entry {
+                Planned::Dir => builder.append_data(&mut header(EntryType::Directory, 0o755), &path, io::empty()),
+                Planned::File(file) => {
+                    let mut header = header(EntryType::Regular, 0o644);
+                    header.set_size(file.as_ref().len() as u64);
+                    builder.append_data(&mut header, &path, file.as_ref())
+                },
+                Planned::Link(target) => builder.append_link(&mut header(EntryType::Symlink, 0o777), &path, target),
+            };
+            result.map_err(|e| io_error(Path::new(&path), e))?;
+        }
+        match builder.into_inner() {
+            Ok(_) => Ok(()),
+            Err(e) => Err(io_error(Path::new(self.get_name()), e)),
+        }
+    }
+}
+impl <T: From<Vec<u8>>> DirMulti<T> {
+    /// Read entries of a tar stream into the directory, creating missing directories on the way.
+    ///
+    /// Regular files replace files of the same name, so the last entry of a path wins like when extracting.
+    /// Symlinks with relative targets become links, other entry types are skipped.
+    /// Paths are resolved like any other path of the tree, so entries can't be placed outside of the directory.
+    /// A name used by entries of different types, like a file `a` next to a directory `a/b`, fails with [`DirError::AmbiguousPath`].
+    pub fn read_tar(&mut self, reader: impl Read) -> Result<(), DirError> {
+        let mut archive = Archive::new(reader);
+        let entries = archive.entries().map_err(|e| io_error(Path::new(self.get_name()), e))?;
+        for entry in entries {
+            let mut entry = entry.map_err(|e| io_error(Path::new(self.get_name()), e))?;
+            let path = entry.path().map_err(|e| io_error(Path::new(self.get_name()), e))?.into_owned();
+            let tree_path = match path.to_str() {
+                Some(tree_path) => TreePath::new(tree_path),
+                None => return Err(DirError::InvalidPath(path.to_string_lossy().into_owned())),
+            };
+            match entry.header().entry_type() {
+                EntryType::Directory => {
+                    let names = resolve_path(self.get_path(), tree_path)?;
+                    self.ensure_flat_dir_mut(&names)?;
+                },
+                EntryType::Regular | EntryType::Continuous => {
+                    let mut bytes = Vec::with_capacity(entry.size() as usize);
+                    entry.read_to_end(&mut bytes).map_err(|e| io_error(&path, e))?;
+                    let (names, name) = resolve_parent(self.get_path(), tree_path)?;
+                    let directory = self.ensure_flat_dir_mut(&names)?;
+                    if directory.directory.contains_key(name) || directory.link.contains_key(name) { return Err(DirError::AmbiguousPath(join_names(&names, name))) }
+                    directory.file.insert(name.to_owned(), T::from(bytes));
+                },
+                EntryType::Symlink => {
+                    let target = entry.link_name().map_err(|e| io_error(&path, e))?;
+                    if let Some(target) = target.as_deref().and_then(link_target) {
+                        let (names, name) = resolve_parent(self.get_path(), tree_path)?;
+                        let directory = self.ensure_flat_dir_mut(&names)?;
+                        if directory.directory.contains_key(name) || directory.file.contains_key(name) { return Err(DirError::AmbiguousPath(join_names(&names, name))) }
+                        directory.link.insert(name.to_owned(), target);
+                    }
+                },
+                _ => {},
+            }
+        }
+        Ok(())
+    }
+}
+impl <T: AsRef<[u8]>> DirMapMulti<T> {
+    /// Write the tree as a tar stream, see [`DirMulti::write_tar`]
+    pub fn write_tar(&self, writer: impl Write) -> Result<(), DirError> {
+        self.directory.write_tar(writer)
+    }
+}
+impl <T: From<Vec<u8>>> DirMapMulti<T> {
+    /// Create tree with the name from a tar stream, see [`DirMulti::read_tar`]
+    ///
+    /// Reading needs `T: From<Vec<u8>>` while [`DirMapMulti::write_tar`] needs `T: AsRef<[u8]>`,
+    /// so a round trip works only with file types implementing both, like `Vec<u8>`.
+    /// ```
+    /// # use pathio::prelude::*;
+    /// let mut tree: PathTree<Vec<u8>> = PathTree::new("Root");
+    /// tree.insert_file_with_parents("docs/readme.md", b"# Hello".to_vec()).unwrap();
+    ///
+    /// let mut archive = Vec::new();
+    /// tree.write_tar(&mut archive).unwrap();
+    /// assert_eq!(PathTree::from_tar("Root", archive.as_slice()).unwrap(), tree);
+    /// ```
+    pub fn from_tar(name: impl Borrow<str>, reader: impl Read) -> Result<Self, DirError> {
+        let mut tree = DirMapMulti::new(name);
+        tree.directory.read_tar(reader)?;
+        Ok(tree)
+    }
+
+    /// Read entries of a tar stream into the tree, see [`DirMulti::read_tar`]
+    pub fn read_tar(&mut self, reader: impl Read) -> Result<(), DirError> {
+        self.directory.read_tar(reader)
+    }
+}
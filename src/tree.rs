@@ -1,8 +1,14 @@
-use ahash::AHashMap as HashMap;
-use colored::Colorize;
 use thiserror::Error;
 use std::borrow::Borrow;
 
+use crate::{Component as PathComponent, TreePath, TreePathBuf};
+use crate::storage::{DirStorage, storage_remove};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Deserializer, de::{self, MapAccess, Visitor}, ser::{Serialize, SerializeStruct, Serializer}};
+
+#[cfg(feature = "bevy")]
+use bevy::prelude::Component;
 
 // #===============================#
 // #=== GENERIC IMPLEMENTATIONS ===#
@@ -34,24 +40,187 @@ pub enum DirError {
     /// Error that happens when you try to locate a file that doesn't exist.
     #[error("Unable to locate '{0:}' file")]
     NoFile (String),
+
+    /// Error that happens when cached name, path or depth of a directory doesn't match its location in the hierarchy.
+    #[error("Cached location of '{0:}' directory is out of sync")]
+    Desync (String),
+
+    /// Error that happens when a glob pattern can't be parsed.
+    #[error("Glob pattern '{0:}' is not valid")]
+    InvalidPattern (String),
+
+    /// Error that happens when you try to locate a link that doesn't exist.
+    #[error("Unable to locate '{0:}' link")]
+    NoLink (String),
+
+    /// Error that happens when resolving a path follows more links than [`MAX_LINK_HOPS`](crate::MAX_LINK_HOPS), usually because they form a loop.
+    #[error("Too many levels of links while resolving '{0:}'")]
+    LinkLoop (String),
+
+    /// Error that happens when reading from or writing to the real file system fails.
+    #[error("I/O error at {0:}")]
+    Io (String),
+
+    /// Error that happens when flattened paths use the same name both for a file and for a directory.
+    #[error("Path '{0:}' is used both as a file and as a directory")]
+    AmbiguousPath (String),
+}
+
+
+/// Check if name can be used for a directory or file, path is used in the error message
+pub(crate) fn validate_name(name: &str, path: &str) -> Result<(), DirError> {
+    if name.is_empty() || name == "." || name == ".." || name.contains('/') {
+        Err(DirError::InvalidPath(path.to_owned()))
+    } else {
+        Ok(())
+    }
+}
+
+/// Join directory path with a name, empty path is the root
+pub(crate) fn join_path(path: &str, name: &str) -> String {
+    if path.is_empty() { name.to_owned() } else { path.to_owned() + "/" + name }
+}
+
+/// Resolve path relative to the directory located at `base` into names leading from that directory to the target.
+/// A leading '/' starts at the root and '..' steps to the parent, staying put at the root. Paths resolving outside of the directory are not allowed.
+pub(crate) fn resolve_path<'a>(base: &str, path: &'a TreePath) -> Result<Vec<&'a str>, DirError> {
+    let base_names: Vec<&str> = base.split('/').filter(|name| !name.is_empty()).collect();
+    let mut kept = base_names.len();
+    let mut names = Vec::new();
+    for component in path.components() {
+        match component {
+            PathComponent::RootDir => kept = 0,
+            PathComponent::ParentDir => if names.pop().is_none() { kept = kept.saturating_sub(1) },
+            PathComponent::Normal(name) => names.push(name),
+        }
+    }
+    let left = &base_names[kept..];
+    if !names.starts_with(left) { return Err(DirError::InvalidPath(path.to_string())) }
+    Ok(names.split_off(left.len()))
+}
+
+/// Resolve path like [`resolve_path`] and split off the last name, path resolving to the directory itself is not allowed
+pub(crate) fn resolve_parent<'a>(base: &str, path: &'a TreePath) -> Result<(Vec<&'a str>, &'a str), DirError> {
+    let mut names = resolve_path(base, path)?;
+    match names.pop() {
+        Some(name) => Ok((names, name)),
+        None => Err(DirError::InvalidPath(path.to_string())),
+    }
+}
+
+/// Join resolved names of a directory with a name into a normalised path
+pub(crate) fn join_names(names: &[&str], name: &str) -> String {
+    let path = names.iter().fold(String::new(), |path, branch| join_path(&path, branch));
+    join_path(&path, name)
+}
+
+/// Directory that can be the target of a move or copy, shared by every directory type
+pub(crate) trait DirTarget: Sized {
+    /// Returns true if the directory holds no files, subdirectories or links, so it can be replaced
+    fn is_vacant(&self) -> bool;
+
+    /// Borrow subdirectory with the name
+    fn target_dir(&self, name: &str) -> Option<&Self>;
+
+    /// Returns true if a link uses the name
+    fn has_link(&self, name: &str) -> bool;
+}
+impl <T> DirTarget for DirMulti<T> {
+    fn is_vacant(&self) -> bool {
+        self.file.is_empty() && self.directory.is_empty() && self.link.is_empty()
+    }
+
+    fn target_dir(&self, name: &str) -> Option<&Self> {
+        self.directory.get(name)
+    }
+
+    fn has_link(&self, name: &str) -> bool {
+        self.link.contains_key(name)
+    }
+}
+impl <T> DirTarget for DirSingle<T> {
+    fn is_vacant(&self) -> bool {
+        self.file.is_none() && self.directory.is_empty() && self.link.is_empty()
+    }
+
+    fn target_dir(&self, name: &str) -> Option<&Self> {
+        self.directory.get(name)
+    }
+
+    fn has_link(&self, name: &str) -> bool {
+        self.link.contains_key(name)
+    }
+}
+
+/// Borrow directory located at the resolved names of any directory type
+fn descend_target<'a, D: DirTarget>(root: &'a D, names: &[&str]) -> Result<&'a D, DirError> {
+    let mut directory = root;
+    for name in names {
+        directory = directory.target_dir(name).ok_or_else(|| DirError::NoDir((*name).to_owned()))?;
+    }
+    Ok(directory)
 }
 
+/// Resolve where a directory moved or copied like `mv` or `cp -r` ends up, returns names of the target parent, target name and its path.
+/// If the destination is an existing directory, the target is placed inside. A target equal to the source is returned right away,
+/// otherwise it can't lie inside the source and only a vacant directory can be in its way, replaced if `overwrite` is set.
+pub(crate) fn resolve_target<'a, D: DirTarget>(root: &D, source_parent: &[&'a str], source_name: &'a str, destination_names: &'a [&'a str], destination: &TreePath, overwrite: bool) -> Result<(&'a [&'a str], &'a str, String), DirError> {
+    let (target_parent, target_name) = match descend_target(root, destination_names) {
+        Ok(_) => (destination_names, source_name),
+        Err(_) => match destination_names.split_last() {
+            Some((name, parent)) => (parent, *name),
+            None => return Err(DirError::InvalidPath(destination.to_string())),
+        },
+    };
+    let target = join_names(target_parent, target_name);
+    let (mut source_names, mut target_names) = (source_parent.to_vec(), target_parent.to_vec());
+    source_names.push(source_name);
+    target_names.push(target_name);
+    if source_names == target_names { return Ok((target_parent, target_name, target)) }
+    if target_names.starts_with(&source_names) { return Err(DirError::InvalidPath(target)) }
+    let parent = descend_target(root, target_parent)?;
+    if parent.has_link(target_name) { return Err(DirError::NameInUse(target)) }
+    if let Some(existing) = parent.target_dir(target_name) {
+        if !overwrite || !existing.is_vacant() { return Err(DirError::NameInUse(target)) }
+    }
+    Ok((target_parent, target_name, target))
+}
+
+
+pub trait PathTreeInit {
+    /// Creates a new pathtree with the given name
+    fn new(name: impl Borrow<str>) -> Self;
+}
+pub trait DirectoryInit {
+    /// Create new unassigned directory, which acts as its own root until it is inserted
+    fn new() -> Self;
+}
 
 pub trait DirHierarchy<D> {
     /// Adds subdirectory directly to this directory, returns new subdirectories' name
     fn add_dir(&mut self, name: impl Borrow<str>, directory: D) -> Result<String, DirError>;
 
     /// Inserts subdirectory to self or any subdirectory, returns inserted subdirectories' name
-    fn insert_dir(&mut self, path: impl Borrow<str>, directory: D,) -> Result<String, DirError>;
+    fn insert_dir(&mut self, path: impl AsRef<TreePath>, directory: D,) -> Result<String, DirError>;
 
     /// Creates subdirectory in root or any subdirectory, returns new subdirectories' name
-    fn create_dir(&mut self, path: impl Borrow<str>) -> Result<String, DirError>;
+    fn create_dir(&mut self, path: impl AsRef<TreePath>) -> Result<String, DirError>;
+
+    /// Creates subdirectory in root or any subdirectory together with all missing parents, returns paths of the created directories
+    fn create_dir_all(&mut self, path: impl AsRef<TreePath>) -> Result<Vec<String>, DirError>;
 
-    /// Removes directory from self and returns it
+    /// Removes directory from self and returns it detached, as its own root
     fn take_dir(&mut self, name: impl Borrow<str>) -> Result<D, DirError>;
 
     /// Removes directory from self or any subdirectory and returns it
-    fn remove_dir(&mut self, path: impl Borrow<str>) -> Result<D, DirError>;
+    fn remove_dir(&mut self, path: impl AsRef<TreePath>) -> Result<D, DirError>;
+
+    /// Renames directory in self or any subdirectory, returns its new path
+    fn rename_dir(&mut self, path: impl AsRef<TreePath>, name: impl Borrow<str>) -> Result<String, DirError>;
+
+    /// Moves directory to another location like `mv`, returns its new path. If the destination is an existing directory, it is moved inside.
+    /// Existing empty directory in the way is replaced only if `overwrite` is set.
+    fn move_dir(&mut self, source: impl AsRef<TreePath>, destination: impl AsRef<TreePath>, overwrite: bool) -> Result<String, DirError>;
 
     /// Borrow directory from self
     fn obtain_dir(&self, name: impl Borrow<str>) -> Result<&D, DirError>;
@@ -60,10 +229,10 @@ pub trait DirHierarchy<D> {
     fn obtain_dir_mut(&mut self, name: impl Borrow<str>) -> Result<&mut D, DirError>;
   
     /// Borrow directory from self or any subdirectory
-    fn borrow_dir(&self, path: impl Borrow<str>) -> Result<&D, DirError>;
+    fn borrow_dir(&self, path: impl AsRef<TreePath>) -> Result<&D, DirError>;
 
     /// Borrow directory from self or any subdirectory
-    fn borrow_dir_mut(&mut self, path: impl Borrow<str>) -> Result<&mut D, DirError>;
+    fn borrow_dir_mut(&mut self, path: impl AsRef<TreePath>) -> Result<&mut D, DirError>;
 
     /// Merges DirMap or Dir content into itself
     fn merge(&mut self, directory: impl Into<D>) -> Result<(), DirError>;
@@ -91,13 +260,19 @@ pub trait DirFile<T> {
     fn add_file(&mut self, file: T) -> Option<T>;
 
     /// Inserts file to self or any subdirectory and return existing one
-    fn insert_file(&mut self, path: impl Borrow<str>, file: T) -> Result<Option<T>, DirError>;
+    fn insert_file(&mut self, path: impl AsRef<TreePath>, file: T) -> Result<Option<T>, DirError>;
+
+    /// Inserts file to self or any subdirectory while creating all missing directories, returns paths of the created directories and existing file
+    fn insert_file_with_parents(&mut self, path: impl AsRef<TreePath>, file: T) -> Result<(Vec<String>, Option<T>), DirError>;
 
     /// Removes file from self and returns it
     fn take_file(&mut self) -> Option<T>;
 
     /// Removes file from self or any subdirectory and returns it
-    fn remove_file(&mut self, path: impl Borrow<str>) -> Result<Option<T>, DirError>;
+    fn remove_file(&mut self, path: impl AsRef<TreePath>) -> Result<Option<T>, DirError>;
+
+    /// Moves file from one directory to another, returns path of the destination directory. Existing file in the destination is replaced only if `overwrite` is set.
+    fn move_file(&mut self, source: impl AsRef<TreePath>, destination: impl AsRef<TreePath>, overwrite: bool) -> Result<String, DirError>;
 
     /// Borrow file from self
     fn obtain_file(&self) -> Option<&T>;
@@ -106,23 +281,33 @@ pub trait DirFile<T> {
     fn obtain_file_mut(&mut self) -> Option<&mut T>;
 
     /// Borrow file from self or any subdirectory
-    fn borrow_file(&self, path: impl Borrow<str>) -> Result<Option<&T>, DirError>;
+    fn borrow_file(&self, path: impl AsRef<TreePath>) -> Result<Option<&T>, DirError>;
     
     /// Borrow file from self or any subdirectory
-    fn borrow_file_mut(&mut self, path: impl Borrow<str>) -> Result<Option<&mut T>, DirError>;
+    fn borrow_file_mut(&mut self, path: impl AsRef<TreePath>) -> Result<Option<&mut T>, DirError>;
 }
 pub trait DirFiles<T> {
     /// Adds file directly to this directory
     fn add_file(&mut self, name: impl Borrow<str>, file: T) -> Result<(), DirError>;
 
     /// Inserts file to self or any subdirectory
-    fn insert_file(&mut self, path: impl Borrow<str>, file: T) -> Result<(), DirError>;
+    fn insert_file(&mut self, path: impl AsRef<TreePath>, file: T) -> Result<(), DirError>;
+
+    /// Inserts file to self or any subdirectory while creating all missing directories, returns paths of the created directories
+    fn insert_file_with_parents(&mut self, path: impl AsRef<TreePath>, file: T) -> Result<Vec<String>, DirError>;
 
     /// Removes file from self and returns it
     fn take_file(&mut self, name: impl Borrow<str>) -> Result<T, DirError>;
 
     /// Removes file from self or any subdirectory and returns it
-    fn remove_file(&mut self, path: impl Borrow<str>) -> Result<T, DirError>;
+    fn remove_file(&mut self, path: impl AsRef<TreePath>) -> Result<T, DirError>;
+
+    /// Renames file in self or any subdirectory, returns its new path
+    fn rename_file(&mut self, path: impl AsRef<TreePath>, name: impl Borrow<str>) -> Result<String, DirError>;
+
+    /// Moves file to another location like `mv`, returns its new path. If the destination is an existing directory, it is moved inside.
+    /// Existing file in the way is replaced only if `overwrite` is set.
+    fn move_file(&mut self, source: impl AsRef<TreePath>, destination: impl AsRef<TreePath>, overwrite: bool) -> Result<String, DirError>;
 
     /// Borrow file from self
     fn obtain_file(&self, name: impl Borrow<str>) -> Result<&T, DirError>;
@@ -131,33 +316,58 @@ pub trait DirFiles<T> {
     fn obtain_file_mut(&mut self, name: impl Borrow<str>) -> Result<&mut T, DirError>;
 
     /// Borrow file from self or any subdirectory
-    fn borrow_file(&self, path: impl Borrow<str>) -> Result<&T, DirError>;
+    fn borrow_file(&self, path: impl AsRef<TreePath>) -> Result<&T, DirError>;
     
     /// Borrow file from self or any subdirectory
-    fn borrow_file_mut(&mut self, path: impl Borrow<str>) -> Result<&mut T, DirError>;
+    fn borrow_file_mut(&mut self, path: impl AsRef<TreePath>) -> Result<&mut T, DirError>;
 }
 
 
+/// [`PathTree`] is a type [`DirMapMulti`], which is a special type immitating **UNIX** file system for storing any generic type `<T>`
+/// 
+/// ```
+/// use pathio::prelude::*;
+/// 
+/// let mut tree: PathTree<usize> = PathTree::new("Root");
+/// tree.create_dir("Folder").unwrap();
+/// tree.insert_file("Folder/number", 10).unwrap();
+/// 
+/// assert_eq!(tree.borrow_file("Folder/number"), Ok(&10));
+/// assert_eq!(tree.borrow_dir("Folder").unwrap().get_path(), "Folder");
+/// ```
+pub type PathTree<T> = DirMapMulti<T>;
+
+/// [`Directory`] is a type [`DirMulti`], which represents a directory in immitating **UNIX** file system for storing any generic type `<T>`
+/// 
+/// ```
+/// use pathio::prelude::*;
+/// 
+/// let mut tree: PathTree<usize> = PathTree::new("Root");
+/// tree.add_dir("Folder", Directory::new()).unwrap();
+/// 
+/// assert_eq!(tree.borrow_dir("Folder").unwrap().get_depth(), 1.0);
+/// ```
+pub type Directory<T> = DirMulti<T>;
 
 
 // #===============================#
 // #=== DIRMAP IMPLEMENTATIONS ===#
 
 
-#[cfg_attr(feature = "serde", derive(Deserialize))]
 #[cfg_attr(feature = "bevy", derive(Component))]
 #[derive(Debug, Default, Clone, PartialEq)]
 pub struct DirMapSingle<T> {
     pub directory: DirSingle<T>,
 }
-impl <T> DirMapSingle<T> {
-    /// # New
-    /// Create new DirMap
-    pub fn new(name: impl Borrow<str>) -> Self {
+impl <T> PathTreeInit for DirMapSingle<T> {
+    fn new(name: impl Borrow<str>) -> Self {
         let mut directory = DirSingle::new();
-        directory.name = name.borrow().into();
-        directory.path = "".into();
-        DirMapSingle { directory }
+        directory.name = name.borrow().to_owned();
+        directory.path = "".to_owned();
+
+        DirMapSingle {
+            directory,
+        }
     }
 }
 
@@ -166,22 +376,34 @@ impl <T> DirHierarchy<DirSingle<T>> for DirMapSingle<T> {
         self.directory.add_dir(name, directory)
     }
 
-    fn insert_dir(&mut self, path: impl Borrow<str>, directory: DirSingle<T>,) -> Result<String, DirError>{
+    fn insert_dir(&mut self, path: impl AsRef<TreePath>, directory: DirSingle<T>,) -> Result<String, DirError>{
         self.directory.insert_dir(path, directory)
     }
 
-    fn create_dir(&mut self, path: impl Borrow<str>) -> Result<String, DirError>{
+    fn create_dir(&mut self, path: impl AsRef<TreePath>) -> Result<String, DirError>{
         self.directory.create_dir(path)
     }
 
+    fn create_dir_all(&mut self, path: impl AsRef<TreePath>) -> Result<Vec<String>, DirError>{
+        self.directory.create_dir_all(path)
+    }
+
     fn take_dir(&mut self, name: impl Borrow<str>) -> Result<DirSingle<T>, DirError> {
         self.directory.take_dir(name)
     }
 
-    fn remove_dir(&mut self, path: impl Borrow<str>) -> Result<DirSingle<T>, DirError> {
+    fn remove_dir(&mut self, path: impl AsRef<TreePath>) -> Result<DirSingle<T>, DirError> {
         self.directory.remove_dir(path)
     }
 
+    fn rename_dir(&mut self, path: impl AsRef<TreePath>, name: impl Borrow<str>) -> Result<String, DirError> {
+        self.directory.rename_dir(path, name)
+    }
+
+    fn move_dir(&mut self, source: impl AsRef<TreePath>, destination: impl AsRef<TreePath>, overwrite: bool) -> Result<String, DirError> {
+        self.directory.move_dir(source, destination, overwrite)
+    }
+
     fn obtain_dir(&self, name: impl Borrow<str>) -> Result<&DirSingle<T>, DirError> {
         self.directory.obtain_dir(name)
     }
@@ -190,11 +412,11 @@ impl <T> DirHierarchy<DirSingle<T>> for DirMapSingle<T> {
         self.directory.obtain_dir_mut(name)
     }
   
-    fn borrow_dir(&self, path: impl Borrow<str>) -> Result<&DirSingle<T>, DirError> {
+    fn borrow_dir(&self, path: impl AsRef<TreePath>) -> Result<&DirSingle<T>, DirError> {
         self.directory.borrow_dir(path)
     }
 
-    fn borrow_dir_mut(&mut self, path: impl Borrow<str>) -> Result<&mut DirSingle<T>, DirError> {
+    fn borrow_dir_mut(&mut self, path: impl AsRef<TreePath>) -> Result<&mut DirSingle<T>, DirError> {
         self.directory.borrow_dir_mut(path)
     }
 
@@ -215,7 +437,7 @@ impl <T> DirHierarchy<DirSingle<T>> for DirMapSingle<T> {
     }
 
     fn get_name(&self) -> &String {
-        &self.directory.get_name()
+        self.directory.get_name()
     }
 
     fn get_depth(&self) -> f32 {
@@ -223,7 +445,7 @@ impl <T> DirHierarchy<DirSingle<T>> for DirMapSingle<T> {
     }
 
     fn get_path(&self) -> &String {
-        &self.directory.get_path()
+        self.directory.get_path()
     }
 }
 impl <T> DirFile<T> for DirMapSingle<T> {
@@ -231,18 +453,26 @@ impl <T> DirFile<T> for DirMapSingle<T> {
         self.directory.add_file(file)
     }
 
-    fn insert_file(&mut self, path: impl Borrow<str>, file: T) -> Result<Option<T>, DirError> {
+    fn insert_file(&mut self, path: impl AsRef<TreePath>, file: T) -> Result<Option<T>, DirError> {
         self.directory.insert_file(path, file)
     }
 
+    fn insert_file_with_parents(&mut self, path: impl AsRef<TreePath>, file: T) -> Result<(Vec<String>, Option<T>), DirError> {
+        self.directory.insert_file_with_parents(path, file)
+    }
+
     fn take_file(&mut self) -> Option<T> {
         self.directory.take_file()
     }
 
-    fn remove_file(&mut self, path: impl Borrow<str>) -> Result<Option<T>, DirError> {
+    fn remove_file(&mut self, path: impl AsRef<TreePath>) -> Result<Option<T>, DirError> {
         self.directory.remove_file(path)
     }
 
+    fn move_file(&mut self, source: impl AsRef<TreePath>, destination: impl AsRef<TreePath>, overwrite: bool) -> Result<String, DirError> {
+        self.directory.move_file(source, destination, overwrite)
+    }
+
     fn obtain_file(&self) -> Option<&T> {
         self.directory.obtain_file()
     }
@@ -251,17 +481,26 @@ impl <T> DirFile<T> for DirMapSingle<T> {
         self.directory.obtain_file_mut()
     }
 
-    fn borrow_file(&self, path: impl Borrow<str>) -> Result<Option<&T>, DirError> {
+    fn borrow_file(&self, path: impl AsRef<TreePath>) -> Result<Option<&T>, DirError> {
         self.directory.borrow_file(path)
     }
     
-    fn borrow_file_mut(&mut self, path: impl Borrow<str>) -> Result<Option<&mut T>, DirError> {
+    fn borrow_file_mut(&mut self, path: impl AsRef<TreePath>) -> Result<Option<&mut T>, DirError> {
         self.directory.borrow_file_mut(path)
     }
 }
-impl <T> Into<DirSingle<T>> for DirMapSingle<T>{
-    fn into(self) -> DirSingle<T> {
-        self.directory
+impl <T> DirMapSingle<T> {
+    /// Check that the root is at an empty path and that cached name, path and depth of every directory match its location in the hierarchy
+    pub fn validate(&self) -> Result<(), DirError> {
+        if !self.directory.path.is_empty() || self.directory.depth != 0.0 {
+            return Err(DirError::Desync(self.directory.name.to_owned()));
+        }
+        self.directory.validate()
+    }
+}
+impl <T> From<DirMapSingle<T>> for DirSingle<T> {
+    fn from(value: DirMapSingle<T>) -> Self {
+        value.directory
     }
 }
 
@@ -271,23 +510,37 @@ impl <T:Serialize> Serialize for DirMapSingle<T> {
     where
         S: Serializer,
     {
-        let mut s = serializer.serialize_struct("DirMapSingle", 1)?;
-        s.serialize_field("directory", &self.directory)?;
+        let mut s = serializer.serialize_struct("DirMapSingle", 1 + self.directory.content_len())?;
+        s.serialize_field("name", &self.directory.name)?;
+        self.directory.serialize_content(&mut s)?;
         s.end()
     }
 }
+#[cfg(feature = "serde")]
+impl <'de, T:Deserialize<'de>> Deserialize<'de> for DirMapSingle<T> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let repr = MapRepr::<Option<T>>::deserialize(deserializer)?;
+        let content = DirRepr { file: repr.file, directory: repr.directory, link: repr.link };
+        match DirSingle::from_repr(content, repr.name, "".to_owned(), 0.0) {
+            Ok(directory) => Ok(DirMapSingle { directory }),
+            Err(e) => Err(de::Error::custom(e)),
+        }
+    }
+}
 
 
 
 
-#[cfg_attr(feature = "serde", derive(Deserialize))]
 #[cfg_attr(feature = "bevy", derive(Component))]
 #[derive(Debug, Default, Clone, PartialEq)]
 pub struct DirMapMulti<T> {
     pub directory: DirMulti<T>,
 }
-impl <T> DirMapMulti<T> {
-    pub fn new(name: impl Borrow<str>) -> Self {
+impl <T> PathTreeInit for DirMapMulti<T> {
+    fn new(name: impl Borrow<str>) -> Self {
         let mut directory = DirMulti::new();
         directory.name = name.borrow().to_owned();
         directory.path = "".to_owned();
@@ -302,22 +555,34 @@ impl <T> DirHierarchy<DirMulti<T>> for DirMapMulti<T> {
         self.directory.add_dir(name, directory)
     }
 
-    fn insert_dir(&mut self, path: impl Borrow<str>, directory: DirMulti<T>) -> Result<String, DirError>{
+    fn insert_dir(&mut self, path: impl AsRef<TreePath>, directory: DirMulti<T>) -> Result<String, DirError>{
         self.directory.insert_dir(path, directory)
     }
 
-    fn create_dir(&mut self, path: impl Borrow<str>) -> Result<String, DirError>{
+    fn create_dir(&mut self, path: impl AsRef<TreePath>) -> Result<String, DirError>{
         self.directory.create_dir(path)
     }
 
+    fn create_dir_all(&mut self, path: impl AsRef<TreePath>) -> Result<Vec<String>, DirError>{
+        self.directory.create_dir_all(path)
+    }
+
     fn take_dir(&mut self, name: impl Borrow<str>) -> Result<DirMulti<T>, DirError> {
         self.directory.take_dir(name)
     }
 
-    fn remove_dir(&mut self, path: impl Borrow<str>) -> Result<DirMulti<T>, DirError> {
+    fn remove_dir(&mut self, path: impl AsRef<TreePath>) -> Result<DirMulti<T>, DirError> {
         self.directory.remove_dir(path)
     }
 
+    fn rename_dir(&mut self, path: impl AsRef<TreePath>, name: impl Borrow<str>) -> Result<String, DirError> {
+        self.directory.rename_dir(path, name)
+    }
+
+    fn move_dir(&mut self, source: impl AsRef<TreePath>, destination: impl AsRef<TreePath>, overwrite: bool) -> Result<String, DirError> {
+        self.directory.move_dir(source, destination, overwrite)
+    }
+
     fn obtain_dir(&self, name: impl Borrow<str>) -> Result<&DirMulti<T>, DirError> {
         self.directory.obtain_dir(name)
     }
@@ -326,11 +591,11 @@ impl <T> DirHierarchy<DirMulti<T>> for DirMapMulti<T> {
         self.directory.obtain_dir_mut(name)
     }
   
-    fn borrow_dir(&self, path: impl Borrow<str>) -> Result<&DirMulti<T>, DirError> {
+    fn borrow_dir(&self, path: impl AsRef<TreePath>) -> Result<&DirMulti<T>, DirError> {
         self.directory.borrow_dir(path)
     }
 
-    fn borrow_dir_mut(&mut self, path: impl Borrow<str>) -> Result<&mut DirMulti<T>, DirError> {
+    fn borrow_dir_mut(&mut self, path: impl AsRef<TreePath>) -> Result<&mut DirMulti<T>, DirError> {
         self.directory.borrow_dir_mut(path)
     }
 
@@ -351,7 +616,7 @@ impl <T> DirHierarchy<DirMulti<T>> for DirMapMulti<T> {
     }
 
     fn get_name(&self) -> &String {
-        &self.directory.get_name()
+        self.directory.get_name()
     }
 
     fn get_depth(&self) -> f32 {
@@ -359,7 +624,7 @@ impl <T> DirHierarchy<DirMulti<T>> for DirMapMulti<T> {
     }
 
     fn get_path(&self) -> &String {
-        &self.directory.get_path()
+        self.directory.get_path()
     }
 }
 impl <T> DirFiles<T> for DirMapMulti<T> {
@@ -367,18 +632,30 @@ impl <T> DirFiles<T> for DirMapMulti<T> {
         self.directory.add_file(name, file)
     }
 
-    fn insert_file(&mut self, path: impl Borrow<str>, file: T) -> Result<(), DirError>{
+    fn insert_file(&mut self, path: impl AsRef<TreePath>, file: T) -> Result<(), DirError>{
         self.directory.insert_file(path, file)
     }
 
+    fn insert_file_with_parents(&mut self, path: impl AsRef<TreePath>, file: T) -> Result<Vec<String>, DirError>{
+        self.directory.insert_file_with_parents(path, file)
+    }
+
     fn take_file(&mut self, name: impl Borrow<str>) -> Result<T, DirError> {
         self.directory.take_file(name)
     }
 
-    fn remove_file(&mut self, path: impl Borrow<str>) -> Result<T, DirError> {
+    fn remove_file(&mut self, path: impl AsRef<TreePath>) -> Result<T, DirError> {
         self.directory.remove_file(path)
     }
 
+    fn rename_file(&mut self, path: impl AsRef<TreePath>, name: impl Borrow<str>) -> Result<String, DirError> {
+        self.directory.rename_file(path, name)
+    }
+
+    fn move_file(&mut self, source: impl AsRef<TreePath>, destination: impl AsRef<TreePath>, overwrite: bool) -> Result<String, DirError> {
+        self.directory.move_file(source, destination, overwrite)
+    }
+
     fn obtain_file(&self, name: impl Borrow<str>) -> Result<&T, DirError> {
         self.directory.obtain_file(name)
     }
@@ -387,17 +664,26 @@ impl <T> DirFiles<T> for DirMapMulti<T> {
         self.directory.obtain_file_mut(name)
     }
 
-    fn borrow_file(&self, path: impl Borrow<str>) -> Result<&T, DirError> {
+    fn borrow_file(&self, path: impl AsRef<TreePath>) -> Result<&T, DirError> {
         self.directory.borrow_file(path)
     }
     
-    fn borrow_file_mut(&mut self, path: impl Borrow<str>) -> Result<&mut T, DirError> {
+    fn borrow_file_mut(&mut self, path: impl AsRef<TreePath>) -> Result<&mut T, DirError> {
         self.directory.borrow_file_mut(path)
     }
 }
-impl <T> Into<DirMulti<T>> for DirMapMulti<T>{
-    fn into(self) -> DirMulti<T> {
-        self.directory
+impl <T> DirMapMulti<T> {
+    /// Check that the root is at an empty path and that cached name, path and depth of every directory match its location in the hierarchy
+    pub fn validate(&self) -> Result<(), DirError> {
+        if !self.directory.path.is_empty() || self.directory.depth != 0.0 {
+            return Err(DirError::Desync(self.directory.name.to_owned()));
+        }
+        self.directory.validate()
+    }
+}
+impl <T> From<DirMapMulti<T>> for DirMulti<T> {
+    fn from(value: DirMapMulti<T>) -> Self {
+        value.directory
     }
 }
 
@@ -407,18 +693,32 @@ impl <T:Serialize> Serialize for DirMapMulti<T> {
     where
         S: Serializer,
     {
-        let mut s = serializer.serialize_struct("DirMapMulti", 1)?;
-        s.serialize_field("directory", &self.directory)?;
+        let mut s = serializer.serialize_struct("DirMapMulti", 1 + self.directory.content_len())?;
+        s.serialize_field("name", &self.directory.name)?;
+        self.directory.serialize_content(&mut s)?;
         s.end()
     }
 }
+#[cfg(feature = "serde")]
+impl <'de, T:Deserialize<'de>> Deserialize<'de> for DirMapMulti<T> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let repr = MapRepr::<Entries<T>>::deserialize(deserializer)?;
+        let content = DirRepr { file: repr.file, directory: repr.directory, link: repr.link };
+        match DirMulti::from_repr(content, repr.name, "".to_owned(), 0.0) {
+            Ok(directory) => Ok(DirMapMulti { directory }),
+            Err(e) => Err(de::Error::custom(e)),
+        }
+    }
+}
 
 
 // #===========================#
 // #=== DIR IMPLEMENTATIONS ===#
 
 
-#[cfg_attr(feature = "serde", derive(Deserialize))]
 #[cfg_attr(feature = "bevy", derive(Component))]
 #[derive(Debug, Default, Clone, PartialEq)]
 pub struct DirSingle<T> {
@@ -429,50 +729,86 @@ pub struct DirSingle<T> {
 
     //# DATA =======
     pub file: Option<T>,
-    pub directory: HashMap<String, DirSingle<T>>,
+    pub directory: DirStorage<DirSingle<T>>,
+    pub link: DirStorage<TreePathBuf>,
 }
-impl <T> DirSingle<T> {
-    pub fn new() -> Self {
+impl <T> DirectoryInit for DirSingle<T> {
+    fn new() -> Self {
         DirSingle {
             name: "UNASSIGNED DIRECTORY".to_owned(),
-            path: "EMPTY PATH".to_owned(),
+            path: "".to_owned(),
             depth: 0.0,
 
             file: None,
-            directory: HashMap::new(),
+            directory: DirStorage::default(),
+            link: DirStorage::default(),
         }
     }
 }
 impl <T> DirSingle<T> {
-    /// Generate overview of the inner tree and write the mapped output to the given string with data formatted to a certain level depth
-    pub(crate) fn cascade_tree(&self, mut string: String, level: u32, param: &str) -> String {
-        if !param.contains("no-dir") {
-            if let Some(_) = self.file {
-                let mut text = String::from("\n  ");
-                for _ in 0..level { text += "|    " }
-                text += "|-> ";
-                string = format!("{}{}{}", string, text.black(), "FILE".bold().bright_cyan());
-            }
+    /// Set cached name, path and depth of this directory and recursively recompute them for the whole subtree
+    pub(crate) fn reroot(&mut self, name: impl Borrow<str>, path: String, depth: f32) {
+        self.name = name.borrow().to_owned();
+        for (name, directory) in &mut self.directory {
+            directory.reroot(name.as_str(), join_path(&path, name), depth + 1.0);
+        }
+        self.path = path;
+        self.depth = depth;
+    }
+
+    /// Check that cached name, path and depth of every subdirectory match its location in the hierarchy
+    /// and that no link shares its name with a subdirectory
+    pub fn validate(&self) -> Result<(), DirError> {
+        for name in self.link.keys() {
+            if self.directory.contains_key(name) { return Err(DirError::NameInUse(join_path(&self.path, name))) }
         }
         for (name, directory) in &self.directory {
-            if name.starts_with('.') {continue;}
-            let mut text = String::from("\n  ");
-            for _ in 0..level { text += "|    " }
-            text += "|-> ";
-            string = format!("{}{}{}", string, text.black(), name.bold().yellow());
-            string = directory.cascade_tree(string, level + 1, param);
+            let path = join_path(&self.path, name);
+            if directory.name != *name || directory.path != path || directory.depth != self.depth + 1.0 {
+                return Err(DirError::Desync(path));
+            }
+            directory.validate()?;
         }
-        string
+        Ok(())
+    }
+
+    /// Borrow directory located at the resolved names
+    pub(crate) fn descend(&self, names: &[&str]) -> Result<&DirSingle<T>, DirError> {
+        let mut directory = self;
+        for name in names {
+            directory = directory.obtain_dir(*name)?;
+        }
+        Ok(directory)
+    }
+
+    /// Mutably borrow directory located at the resolved names
+    pub(crate) fn descend_mut(&mut self, names: &[&str]) -> Result<&mut DirSingle<T>, DirError> {
+        let mut directory = self;
+        for name in names {
+            directory = directory.obtain_dir_mut(*name)?;
+        }
+        Ok(directory)
+    }
+
+    /// Mutably borrow directory located at the resolved names, creating every missing directory on the way and recording their paths
+    pub(crate) fn ensure_dir_mut(&mut self, names: &[&str], created: &mut Vec<String>) -> Result<&mut DirSingle<T>, DirError> {
+        let mut directory = self;
+        for (i, name) in names.iter().enumerate() {
+            if !directory.directory.contains_key(*name) {
+                directory.add_dir(*name, DirSingle::new())?;
+                created.push(join_names(&names[..i], name));
+            }
+            directory = directory.obtain_dir_mut(*name)?;
+        }
+        Ok(directory)
     }
 }
 impl <T> DirHierarchy<DirSingle<T>> for DirSingle<T> {
     fn add_dir(&mut self, name: impl Borrow<str>, mut directory: DirSingle<T>) -> Result<String, DirError>{
         if !name.borrow().is_empty() {
-            if name.borrow() == "." { return Err(DirError::NameInUse("The special symbol '.' is used to refer to 'self' and is not available for use".to_owned())) }
-            if self.directory.contains_key(name.borrow()) == false {
-                directory.name = name.borrow().to_owned();
-                directory.path = if self.path.is_empty() { name.borrow().to_owned() } else { self.path.to_owned() + "/" + name.borrow() };
-                directory.depth = self.depth + 1.0;
+            validate_name(name.borrow(), name.borrow())?;
+            if !self.directory.contains_key(name.borrow()) && !self.link.contains_key(name.borrow()) {
+                directory.reroot(name.borrow(), join_path(&self.path, name.borrow()), self.depth + 1.0);
                 self.directory.insert(name.borrow().to_owned(), directory);
                 Ok(name.borrow().to_owned())
             } else {
@@ -481,50 +817,84 @@ impl <T> DirHierarchy<DirSingle<T>> for DirSingle<T> {
         } else {
             let mut generated_name = format!(".||#:{}", self.directory.len());
             let mut i = 0;
-            while self.directory.contains_key(&generated_name) == true {
+            while self.directory.contains_key(&generated_name) {
                 generated_name = format!(".||#:{}", self.directory.len()+i);
                 i += 1;
                 if i > 100 { return Err(DirError::InvalidPath("Failed to generate name, max threshold reached!".to_owned())); }
             }
-            directory.name = generated_name.to_owned();
-            directory.path = if self.path.is_empty() { generated_name.to_owned() } else { self.path.to_owned() + "/" + &generated_name };
-            directory.depth = self.depth + 1.0;
+            directory.reroot(generated_name.as_str(), join_path(&self.path, &generated_name), self.depth + 1.0);
             self.directory.insert(generated_name.to_owned(), directory);
             Ok(generated_name)
         }
     }
 
-    fn insert_dir(&mut self, path: impl Borrow<str>, directory: DirSingle<T>) -> Result<String, DirError>{
-        match path.borrow().rsplit_once('/'){
-            None => self.add_dir(path, directory),
-            Some ((directory_path, name)) => match self.borrow_dir_mut(directory_path) {
-                Ok(borrowed_directory) => borrowed_directory.add_dir(name, directory),
-                Err(e) => Err(e),
-            }
+    fn insert_dir(&mut self, path: impl AsRef<TreePath>, directory: DirSingle<T>) -> Result<String, DirError>{
+        if path.as_ref().as_str().is_empty() { return self.add_dir("", directory) }
+        let (names, name) = resolve_parent(&self.path, path.as_ref())?;
+        match self.descend_mut(&names) {
+            Ok(borrowed_directory) => borrowed_directory.add_dir(name, directory),
+            Err(e) => Err(e),
         }
     }
 
-    fn create_dir(&mut self, path: impl Borrow<str>) -> Result<String, DirError>{
+    fn create_dir(&mut self, path: impl AsRef<TreePath>) -> Result<String, DirError>{
         self.insert_dir(path, DirSingle::new())
     }
 
+    fn create_dir_all(&mut self, path: impl AsRef<TreePath>) -> Result<Vec<String>, DirError>{
+        let names = resolve_path(&self.path, path.as_ref())?;
+        let mut created = Vec::new();
+        match self.ensure_dir_mut(&names, &mut created) {
+            Ok(_) => Ok(created),
+            Err(e) => Err(e),
+        }
+    }
+
     fn take_dir(&mut self, name: impl Borrow<str>) -> Result<DirSingle<T>, DirError> {
-        match self.directory.remove(name.borrow()) {
-            Some(directory) => Ok(directory),
+        match storage_remove(&mut self.directory, name.borrow()) {
+            Some(mut directory) => {
+                directory.reroot(name, String::new(), 0.0);
+                Ok(directory)
+            },
             None => Err(DirError::NoDir(name.borrow().to_owned())),
         }
     }
 
-    fn remove_dir(&mut self, path: impl Borrow<str>) -> Result<DirSingle<T>, DirError> {
-        match path.borrow().split_once('/') {
-            None => self.take_dir(path),
-            Some((branch, remaining_path)) => match self.borrow_dir_mut(branch) {
-                Ok(borrowed_directory) => borrowed_directory.remove_dir(remaining_path),
-                Err(e) => Err(e),
-            },
+    fn remove_dir(&mut self, path: impl AsRef<TreePath>) -> Result<DirSingle<T>, DirError> {
+        let (names, name) = resolve_parent(&self.path, path.as_ref())?;
+        match self.descend_mut(&names) {
+            Ok(borrowed_directory) => borrowed_directory.take_dir(name),
+            Err(e) => Err(e),
         }
     }
 
+    fn rename_dir(&mut self, path: impl AsRef<TreePath>, name: impl Borrow<str>) -> Result<String, DirError> {
+        let (names, old_name) = resolve_parent(&self.path, path.as_ref())?;
+        validate_name(name.borrow(), name.borrow())?;
+        let parent = self.descend_mut(&names)?;
+        let new_path = join_names(&names, name.borrow());
+        if !parent.directory.contains_key(old_name) { return Err(DirError::NoDir(path.as_ref().to_string())) }
+        if old_name == name.borrow() { return Ok(new_path) }
+        if parent.directory.contains_key(name.borrow()) || parent.link.contains_key(name.borrow()) { return Err(DirError::NameInUse(new_path)) }
+        let mut directory = parent.take_dir(old_name)?;
+        directory.reroot(name.borrow(), join_path(&parent.path, name.borrow()), parent.depth + 1.0);
+        parent.directory.insert(name.borrow().to_owned(), directory);
+        Ok(new_path)
+    }
+
+    fn move_dir(&mut self, source: impl AsRef<TreePath>, destination: impl AsRef<TreePath>, overwrite: bool) -> Result<String, DirError> {
+        let (source_parent, source_name) = resolve_parent(&self.path, source.as_ref())?;
+        let destination_names = resolve_path(&self.path, destination.as_ref())?;
+        self.descend(&source_parent)?.obtain_dir(source_name)?;
+        let (target_parent, target_name, target) = resolve_target(self, &source_parent, source_name, &destination_names, destination.as_ref(), overwrite)?;
+        if target_parent == source_parent.as_slice() && target_name == source_name { return Ok(target) }
+        let mut directory = self.descend_mut(&source_parent)?.take_dir(source_name)?;
+        let parent = self.descend_mut(target_parent)?;
+        directory.reroot(target_name, join_path(&parent.path, target_name), parent.depth + 1.0);
+        parent.directory.insert(target_name.to_owned(), directory);
+        Ok(target)
+    }
+
     fn obtain_dir(&self, name: impl Borrow<str>) -> Result<&DirSingle<T>, DirError> {
         if !name.borrow().is_empty() {
             if name.borrow() == "." { return Ok(self) }
@@ -549,70 +919,54 @@ impl <T> DirHierarchy<DirSingle<T>> for DirSingle<T> {
         }
     }
   
-    fn borrow_dir(&self, path: impl Borrow<str>) -> Result<&DirSingle<T>, DirError> {
-        match path.borrow().split_once('/') {
-            None => self.obtain_dir(path),
-            Some((branch, remaining_path)) => match self.obtain_dir(branch) {
-                Ok(borrowed_directory) => borrowed_directory.borrow_dir(remaining_path),
-                Err(e) => Err(e),
-            },
+    fn borrow_dir(&self, path: impl AsRef<TreePath>) -> Result<&DirSingle<T>, DirError> {
+        match resolve_path(&self.path, path.as_ref()) {
+            Ok(names) => self.lookup_dir(&names),
+            Err(e) => Err(e),
         }
     }
 
-    fn borrow_dir_mut(&mut self, path: impl Borrow<str>) -> Result<&mut DirSingle<T>, DirError> {
-        match path.borrow().split_once('/') {
-            None => self.obtain_dir_mut(path),
-            Some((branch, remaining_path)) => match self.obtain_dir_mut(branch) {
-                Ok(borrowed_directory) => borrowed_directory.borrow_dir_mut(remaining_path),
-                Err(e) => Err(e),
-            },
+    fn borrow_dir_mut(&mut self, path: impl AsRef<TreePath>) -> Result<&mut DirSingle<T>, DirError> {
+        match resolve_path(&self.path, path.as_ref()) {
+            Ok(names) => self.lookup_dir_mut(&names),
+            Err(e) => Err(e),
         }
     }
 
     fn merge(&mut self, directory: impl Into<DirSingle<T>>) -> Result<(), DirError> {
         let directory = directory.into();
 
-        if let Some(_) = directory.file {
+        if directory.file.is_some() {
             return Err(DirError::FileConflict);
         }
 
-        for (name, _) in &directory.directory {
-            if self.directory.contains_key(name) {return Err(DirError::DuplicateName(name.to_owned()));}
+        for name in directory.directory.keys() {
+            if self.directory.contains_key(name) || self.link.contains_key(name) {return Err(DirError::DuplicateName(name.to_owned()));}
+        }
+
+        for name in directory.link.keys() {
+            if self.directory.contains_key(name) || self.link.contains_key(name) {return Err(DirError::DuplicateName(name.to_owned()));}
         }
 
         for (name, dir) in directory.directory {
             self.insert_dir(name, dir)?;
         }
 
+        self.link.extend(directory.link);
+
         Ok(())
     }
 
     fn crawl(&self) -> Vec<&DirSingle<T>> {
-        let mut vector = Vec::new();
-        for pair in &self.directory{
-            vector.push(pair.1);
-            let mut content = pair.1.crawl();
-            vector.append(&mut content);
-        }
-        vector
+        self.iter_dirs().map(|(_, directory)| directory).collect()
     }
 
     fn tree(&self) -> String {
-        let text = String::new();
-        format!(
-            "> {}{}",
-            self.name.purple().bold().underline(),
-            self.cascade_tree(text, 0, "")
-        )
+        self.tree_format().to_string()
     }
 
     fn tree_dir(&self) -> String {
-        let text = String::new();
-        format!(
-            "> {}{}",
-            self.name.purple().bold().underline(),
-            self.cascade_tree(text, 0, "no-dir")
-        )
+        self.tree_format().files(false).to_string()
     }
 
     fn get_name(&self) -> &String {
@@ -629,89 +983,142 @@ impl <T> DirHierarchy<DirSingle<T>> for DirSingle<T> {
 }
 impl <T> DirFile<T> for DirSingle<T> {
     fn add_file(&mut self, file: T) -> Option<T>{
-        core::mem::replace(&mut self.file, Some(file))
+        self.file.replace(file)
     }
 
-    fn insert_file(&mut self, path: impl Borrow<str>, file: T) -> Result<Option<T>, DirError>{
-        if path.borrow().is_empty() {
-            Ok(self.add_file(file))
-        } else {
-            match self.borrow_dir_mut(path) {
-                Ok(borrowed_directory) => Ok(borrowed_directory.add_file(file)),
-                Err(e) => Err(e),
-            }
+    fn insert_file(&mut self, path: impl AsRef<TreePath>, file: T) -> Result<Option<T>, DirError>{
+        match self.borrow_dir_mut(path) {
+            Ok(borrowed_directory) => Ok(borrowed_directory.add_file(file)),
+            Err(e) => Err(e),
+        }
+    }
+
+    fn insert_file_with_parents(&mut self, path: impl AsRef<TreePath>, file: T) -> Result<(Vec<String>, Option<T>), DirError>{
+        let names = resolve_path(&self.path, path.as_ref())?;
+        let mut created = Vec::new();
+        match self.ensure_dir_mut(&names, &mut created) {
+            Ok(borrowed_directory) => Ok((created, borrowed_directory.add_file(file))),
+            Err(e) => Err(e),
         }
     }
 
     fn take_file(&mut self) -> Option<T> {
-        core::mem::replace(&mut self.file, None)
+        self.file.take()
     }
 
-    fn remove_file(&mut self, path: impl Borrow<str>) -> Result<Option<T>, DirError> {
-        match path.borrow().split_once('/') {
-            None => Ok(self.take_file()),
-            Some((branch, remaining_path)) => match self.borrow_dir_mut(branch) {
-                Ok(borrowed_directory) => borrowed_directory.remove_file(remaining_path),
-                Err(e) => Err(e),
-            },
+    fn remove_file(&mut self, path: impl AsRef<TreePath>) -> Result<Option<T>, DirError> {
+        match self.borrow_dir_mut(path) {
+            Ok(borrowed_directory) => Ok(borrowed_directory.take_file()),
+            Err(e) => Err(e),
         }
     }
 
+    fn move_file(&mut self, source: impl AsRef<TreePath>, destination: impl AsRef<TreePath>, overwrite: bool) -> Result<String, DirError> {
+        let source_names = resolve_path(&self.path, source.as_ref())?;
+        let destination_names = resolve_path(&self.path, destination.as_ref())?;
+        let target = destination_names.join("/");
+        if self.descend(&source_names)?.file.is_none() { return Err(DirError::NoFile(source.as_ref().to_string())) }
+        if source_names == destination_names { return Ok(target) }
+        if self.descend(&destination_names)?.file.is_some() && !overwrite { return Err(DirError::NameInUse(target)) }
+        let file = self.descend_mut(&source_names)?.file.take();
+        self.descend_mut(&destination_names)?.file = file;
+        Ok(target)
+    }
+
     fn obtain_file(&self) -> Option<&T> {
-        match &self.file {
-            Some(value) => Some(value),
-            None => None,
-        }
+        self.file.as_ref()
     }
     
     fn obtain_file_mut(&mut self) -> Option<&mut T> {
-        match &mut self.file {
-            Some(value) => Some(value),
-            None => None,
-        }
+        self.file.as_mut()
     }
 
-    fn borrow_file(&self, path: impl Borrow<str>) -> Result<Option<&T> , DirError> {
-        match path.borrow().split_once('/') {
-            None => Ok(self.obtain_file()),
-            Some((branch, remaining_path)) => match self.obtain_dir(branch) {
-                Ok(borrowed_directory) => borrowed_directory.borrow_file(remaining_path),
-                Err(e) => Err(e),
-            },
+    fn borrow_file(&self, path: impl AsRef<TreePath>) -> Result<Option<&T> , DirError> {
+        match self.borrow_dir(path) {
+            Ok(borrowed_directory) => Ok(borrowed_directory.obtain_file()),
+            Err(e) => Err(e),
         }
     }
     
-    fn borrow_file_mut(&mut self, path: impl Borrow<str>) -> Result<Option<&mut T> , DirError> {
-        match path.borrow().split_once('/') {
-            None => Ok(self.obtain_file_mut()),
-            Some((branch, remaining_path)) => match self.obtain_dir_mut(branch) {
-                Ok(borrowed_directory) => borrowed_directory.borrow_file_mut(remaining_path),
-                Err(e) => Err(e),
-            },
+    fn borrow_file_mut(&mut self, path: impl AsRef<TreePath>) -> Result<Option<&mut T> , DirError> {
+        match self.borrow_dir_mut(path) {
+            Ok(borrowed_directory) => Ok(borrowed_directory.obtain_file_mut()),
+            Err(e) => Err(e),
         }
     }
 }
 
+#[cfg(feature = "serde")]
+impl <T:Serialize> DirSingle<T> {
+    /// Number of non-empty data fields written by [`DirSingle::serialize_content`]
+    fn content_len(&self) -> usize {
+        self.file.is_some() as usize + !self.directory.is_empty() as usize + !self.link.is_empty() as usize
+    }
+
+    /// Write the data fields into the struct, skipping the empty ones
+    fn serialize_content<S: SerializeStruct>(&self, s: &mut S) -> Result<(), S::Error> {
+        match &self.file {
+            Some(file) => s.serialize_field("file", file)?,
+            None => s.skip_field("file")?,
+        }
+        if self.directory.is_empty() { s.skip_field("directory")? } else { s.serialize_field("directory", &self.directory)? }
+        if self.link.is_empty() { s.skip_field("link") } else { s.serialize_field("link", &self.link) }
+    }
+}
+#[cfg(feature = "serde")]
+impl <T> DirSingle<T> {
+    /// Build directory from the deserialized representation and recompute cached name, path and depth for the whole subtree
+    fn from_repr(repr: DirRepr<Option<T>>, name: String, path: String, depth: f32) -> Result<Self, DirError> {
+        let mut directory = DirSingle::new();
+        directory.file = repr.file;
+        for (dir_name, dir) in repr.directory.0 {
+            let dir_path = if path.is_empty() { dir_name.to_owned() } else { path.to_owned() + "/" + &dir_name };
+            validate_name(&dir_name, &dir_path)?;
+            if directory.directory.contains_key(&dir_name) { return Err(DirError::NameInUse(dir_path)); }
+            let subdirectory = DirSingle::from_repr(dir, dir_name.to_owned(), dir_path, depth + 1.0)?;
+            directory.directory.insert(dir_name, subdirectory);
+        }
+        for (link_name, target) in repr.link.0 {
+            let link_path = if path.is_empty() { link_name.to_owned() } else { path.to_owned() + "/" + &link_name };
+            validate_name(&link_name, &link_path)?;
+            if directory.link.contains_key(&link_name) || directory.directory.contains_key(&link_name) { return Err(DirError::NameInUse(link_path)); }
+            directory.link.insert(link_name, target);
+        }
+        directory.name = name;
+        directory.path = path;
+        directory.depth = depth;
+        Ok(directory)
+    }
+}
 #[cfg(feature = "serde")]
 impl <T:Serialize> Serialize for DirSingle<T> {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: Serializer,
     {
-        let mut s = serializer.serialize_struct("DirSingle", 5)?;
-        s.serialize_field("name", &self.name)?;
-        s.serialize_field("path", &self.path)?;
-        s.serialize_field("depth", &self.depth)?;
-        s.serialize_field("file", &self.file)?;
-        s.serialize_field("directory", &self.directory)?;
+        let mut s = serializer.serialize_struct("DirSingle", self.content_len())?;
+        self.serialize_content(&mut s)?;
         s.end()
     }
 }
+#[cfg(feature = "serde")]
+impl <'de, T:Deserialize<'de>> Deserialize<'de> for DirSingle<T> {
+    /// Deserialized directory is detached, so the cached paths of its subtree are relative to itself
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let repr = DirRepr::<Option<T>>::deserialize(deserializer)?;
+        match DirSingle::from_repr(repr, DirSingle::<T>::new().name, "".to_owned(), 0.0) {
+            Ok(directory) => Ok(directory),
+            Err(e) => Err(de::Error::custom(e)),
+        }
+    }
+}
 
 
 
 
-#[cfg_attr(feature = "serde", derive(Deserialize))]
 #[cfg_attr(feature = "bevy", derive(Component))]
 #[derive(Debug, Default, Clone, PartialEq)]
 pub struct DirMulti<T> {
@@ -721,52 +1128,87 @@ pub struct DirMulti<T> {
     depth: f32,
 
     //# DATA =======
-    pub file: HashMap<String, T>,
-    pub directory: HashMap<String, DirMulti<T>>,
+    pub file: DirStorage<T>,
+    pub directory: DirStorage<DirMulti<T>>,
+    pub link: DirStorage<TreePathBuf>,
 }
-impl <T> DirMulti<T> {
-    pub fn new() -> Self {
+impl <T> DirectoryInit for DirMulti<T> {
+    fn new() -> Self {
         DirMulti {
             name: "UNASSIGNED DIRECTORY".to_owned(),
-            path: "EMPTY PATH".to_owned(),
+            path: "".to_owned(),
             depth: 0.0,
 
-            file: HashMap::new(),
-            directory: HashMap::new(),
+            file: DirStorage::default(),
+            directory: DirStorage::default(),
+            link: DirStorage::default(),
         }
     }
 }
 impl <T> DirMulti<T> {
-    /// Generate overview of the inner tree and write the mapped output to the given string with data formatted to a certain level depth
-    pub(crate) fn cascade_tree(&self, mut string: String, level: u32, param: &str) -> String {
-        if !param.contains("no-dir") {
-            for (name, _file) in &self.file {
-                if name.starts_with('.') {continue;}
-                let mut text = String::from("\n  ");
-                for _ in 0..level { text += "|    " }
-                text += "|-> ";
-                string = format!("{}{}{}", string, text.black(), name.bold().bright_cyan());
-            }
+    /// Set cached name, path and depth of this directory and recursively recompute them for the whole subtree
+    pub(crate) fn reroot(&mut self, name: impl Borrow<str>, path: String, depth: f32) {
+        self.name = name.borrow().to_owned();
+        for (name, directory) in &mut self.directory {
+            directory.reroot(name.as_str(), join_path(&path, name), depth + 1.0);
+        }
+        self.path = path;
+        self.depth = depth;
+    }
+
+    /// Check that cached name, path and depth of every subdirectory match its location in the hierarchy
+    /// and that no link shares its name with a subdirectory or file
+    pub fn validate(&self) -> Result<(), DirError> {
+        for name in self.link.keys() {
+            if self.directory.contains_key(name) || self.file.contains_key(name) { return Err(DirError::NameInUse(join_path(&self.path, name))) }
         }
         for (name, directory) in &self.directory {
-            if name.starts_with('.') {continue;}
-            let mut text = String::from("\n  ");
-            for _ in 0..level { text += "|    " }
-            text += "|-> ";
-            string = format!("{}{}{}", string, text.black(), name.bold().yellow());
-            string = directory.cascade_tree(string, level + 1, param);
+            let path = join_path(&self.path, name);
+            if directory.name != *name || directory.path != path || directory.depth != self.depth + 1.0 {
+                return Err(DirError::Desync(path));
+            }
+            directory.validate()?;
         }
-        string
+        Ok(())
+    }
+
+    /// Borrow directory located at the resolved names
+    pub(crate) fn descend(&self, names: &[&str]) -> Result<&DirMulti<T>, DirError> {
+        let mut directory = self;
+        for name in names {
+            directory = directory.obtain_dir(*name)?;
+        }
+        Ok(directory)
+    }
+
+    /// Mutably borrow directory located at the resolved names
+    pub(crate) fn descend_mut(&mut self, names: &[&str]) -> Result<&mut DirMulti<T>, DirError> {
+        let mut directory = self;
+        for name in names {
+            directory = directory.obtain_dir_mut(*name)?;
+        }
+        Ok(directory)
+    }
+
+    /// Mutably borrow directory located at the resolved names, creating every missing directory on the way and recording their paths
+    pub(crate) fn ensure_dir_mut(&mut self, names: &[&str], created: &mut Vec<String>) -> Result<&mut DirMulti<T>, DirError> {
+        let mut directory = self;
+        for (i, name) in names.iter().enumerate() {
+            if !directory.directory.contains_key(*name) {
+                directory.add_dir(*name, DirMulti::new())?;
+                created.push(join_names(&names[..i], name));
+            }
+            directory = directory.obtain_dir_mut(*name)?;
+        }
+        Ok(directory)
     }
 }
 impl <T> DirHierarchy<DirMulti<T>> for DirMulti<T> {
     fn add_dir(&mut self, name: impl Borrow<str>, mut directory: DirMulti<T>) -> Result<String, DirError>{
         if !name.borrow().is_empty() {
-            if name.borrow() == "." { return Err(DirError::NameInUse("The special symbol '.' is used to refer to 'self' and is not available for use".to_owned())) }
-            if self.directory.contains_key(name.borrow()) == false {
-                directory.name = name.borrow().to_owned();
-                directory.path = if self.path.is_empty() { name.borrow().to_owned() } else { self.path.to_owned() + "/" + name.borrow() };
-                directory.depth = self.depth + 1.0;
+            validate_name(name.borrow(), name.borrow())?;
+            if !self.directory.contains_key(name.borrow()) && !self.link.contains_key(name.borrow()) {
+                directory.reroot(name.borrow(), join_path(&self.path, name.borrow()), self.depth + 1.0);
                 self.directory.insert(name.borrow().to_owned(), directory);
                 Ok(name.borrow().to_owned())
             } else {
@@ -775,50 +1217,84 @@ impl <T> DirHierarchy<DirMulti<T>> for DirMulti<T> {
         } else {
             let mut generated_name = format!(".||#:{}", self.directory.len());
             let mut i = 0;
-            while self.directory.contains_key(&generated_name) == true {
+            while self.directory.contains_key(&generated_name) {
                 generated_name = format!(".||#:{}", self.directory.len()+i);
                 i += 1;
                 if i > 100 { return Err(DirError::InvalidPath("Failed to generate name, max threshold reached!".to_owned())); }
             }
-            directory.name = generated_name.to_owned();
-            directory.path = if self.path.is_empty() { generated_name.to_owned() } else { self.path.to_owned() + "/" + &generated_name };
-            directory.depth = self.depth + 1.0;
+            directory.reroot(generated_name.as_str(), join_path(&self.path, &generated_name), self.depth + 1.0);
             self.directory.insert(generated_name.to_owned(), directory);
             Ok(generated_name)
         }
     }
 
-    fn insert_dir(&mut self, path: impl Borrow<str>, directory: DirMulti<T>) -> Result<String, DirError>{
-        match path.borrow().rsplit_once('/'){
-            None => self.add_dir(path, directory),
-            Some ((directory_path, name)) => match self.borrow_dir_mut(directory_path) {
-                Ok(borrowed_directory) => borrowed_directory.add_dir(name, directory),
-                Err(e) => Err(e),
-            }
+    fn insert_dir(&mut self, path: impl AsRef<TreePath>, directory: DirMulti<T>) -> Result<String, DirError>{
+        if path.as_ref().as_str().is_empty() { return self.add_dir("", directory) }
+        let (names, name) = resolve_parent(&self.path, path.as_ref())?;
+        match self.descend_mut(&names) {
+            Ok(borrowed_directory) => borrowed_directory.add_dir(name, directory),
+            Err(e) => Err(e),
         }
     }
 
-    fn create_dir(&mut self, path: impl Borrow<str>) -> Result<String, DirError>{
+    fn create_dir(&mut self, path: impl AsRef<TreePath>) -> Result<String, DirError>{
         self.insert_dir(path, DirMulti::new())
     }
 
+    fn create_dir_all(&mut self, path: impl AsRef<TreePath>) -> Result<Vec<String>, DirError>{
+        let names = resolve_path(&self.path, path.as_ref())?;
+        let mut created = Vec::new();
+        match self.ensure_dir_mut(&names, &mut created) {
+            Ok(_) => Ok(created),
+            Err(e) => Err(e),
+        }
+    }
+
     fn take_dir(&mut self, name: impl Borrow<str>) -> Result<DirMulti<T>, DirError> {
-        match self.directory.remove(name.borrow()) {
-            Some(directory) => Ok(directory),
+        match storage_remove(&mut self.directory, name.borrow()) {
+            Some(mut directory) => {
+                directory.reroot(name, String::new(), 0.0);
+                Ok(directory)
+            },
             None => Err(DirError::NoDir(name.borrow().to_owned())),
         }
     }
 
-    fn remove_dir(&mut self, path: impl Borrow<str>) -> Result<DirMulti<T>, DirError> {
-        match path.borrow().split_once('/') {
-            None => self.take_dir(path),
-            Some((branch, remaining_path)) => match self.borrow_dir_mut(branch) {
-                Ok(borrowed_directory) => borrowed_directory.remove_dir(remaining_path),
-                Err(e) => Err(e),
-            },
+    fn remove_dir(&mut self, path: impl AsRef<TreePath>) -> Result<DirMulti<T>, DirError> {
+        let (names, name) = resolve_parent(&self.path, path.as_ref())?;
+        match self.descend_mut(&names) {
+            Ok(borrowed_directory) => borrowed_directory.take_dir(name),
+            Err(e) => Err(e),
         }
     }
 
+    fn rename_dir(&mut self, path: impl AsRef<TreePath>, name: impl Borrow<str>) -> Result<String, DirError> {
+        let (names, old_name) = resolve_parent(&self.path, path.as_ref())?;
+        validate_name(name.borrow(), name.borrow())?;
+        let parent = self.descend_mut(&names)?;
+        let new_path = join_names(&names, name.borrow());
+        if !parent.directory.contains_key(old_name) { return Err(DirError::NoDir(path.as_ref().to_string())) }
+        if old_name == name.borrow() { return Ok(new_path) }
+        if parent.directory.contains_key(name.borrow()) || parent.link.contains_key(name.borrow()) { return Err(DirError::NameInUse(new_path)) }
+        let mut directory = parent.take_dir(old_name)?;
+        directory.reroot(name.borrow(), join_path(&parent.path, name.borrow()), parent.depth + 1.0);
+        parent.directory.insert(name.borrow().to_owned(), directory);
+        Ok(new_path)
+    }
+
+    fn move_dir(&mut self, source: impl AsRef<TreePath>, destination: impl AsRef<TreePath>, overwrite: bool) -> Result<String, DirError> {
+        let (source_parent, source_name) = resolve_parent(&self.path, source.as_ref())?;
+        let destination_names = resolve_path(&self.path, destination.as_ref())?;
+        self.descend(&source_parent)?.obtain_dir(source_name)?;
+        let (target_parent, target_name, target) = resolve_target(self, &source_parent, source_name, &destination_names, destination.as_ref(), overwrite)?;
+        if target_parent == source_parent.as_slice() && target_name == source_name { return Ok(target) }
+        let mut directory = self.descend_mut(&source_parent)?.take_dir(source_name)?;
+        let parent = self.descend_mut(target_parent)?;
+        directory.reroot(target_name, join_path(&parent.path, target_name), parent.depth + 1.0);
+        parent.directory.insert(target_name.to_owned(), directory);
+        Ok(target)
+    }
+
     fn obtain_dir(&self, name: impl Borrow<str>) -> Result<&DirMulti<T>, DirError> {
         if !name.borrow().is_empty() {
             if name.borrow() == "." { return Ok(self) }
@@ -843,34 +1319,32 @@ impl <T> DirHierarchy<DirMulti<T>> for DirMulti<T> {
         }
     }
   
-    fn borrow_dir(&self, path: impl Borrow<str>) -> Result<&DirMulti<T>, DirError> {
-        match path.borrow().split_once('/') {
-            None => self.obtain_dir(path),
-            Some((branch, remaining_path)) => match self.obtain_dir(branch) {
-                Ok(borrowed_directory) => borrowed_directory.borrow_dir(remaining_path),
-                Err(e) => Err(e),
-            },
+    fn borrow_dir(&self, path: impl AsRef<TreePath>) -> Result<&DirMulti<T>, DirError> {
+        match resolve_path(&self.path, path.as_ref()) {
+            Ok(names) => self.lookup_dir(&names),
+            Err(e) => Err(e),
         }
     }
 
-    fn borrow_dir_mut(&mut self, path: impl Borrow<str>) -> Result<&mut DirMulti<T>, DirError> {
-        match path.borrow().split_once('/') {
-            None => self.obtain_dir_mut(path),
-            Some((branch, remaining_path)) => match self.obtain_dir_mut(branch) {
-                Ok(borrowed_directory) => borrowed_directory.borrow_dir_mut(remaining_path),
-                Err(e) => Err(e),
-            },
+    fn borrow_dir_mut(&mut self, path: impl AsRef<TreePath>) -> Result<&mut DirMulti<T>, DirError> {
+        match resolve_path(&self.path, path.as_ref()) {
+            Ok(names) => self.lookup_dir_mut(&names),
+            Err(e) => Err(e),
         }
     }
 
     fn merge(&mut self, directory: impl Into<DirMulti<T>>) -> Result<(), DirError> {
         let directory = directory.into();
-        for (name, _) in &directory.file {
-            if self.file.contains_key(name) {return Err(DirError::DuplicateName(name.to_owned()));}
+        for name in directory.file.keys() {
+            if self.file.contains_key(name) || self.link.contains_key(name) {return Err(DirError::DuplicateName(name.to_owned()));}
+        }
+
+        for name in directory.directory.keys() {
+            if self.directory.contains_key(name) || self.link.contains_key(name) {return Err(DirError::DuplicateName(name.to_owned()));}
         }
 
-        for (name, _) in &directory.directory {
-            if self.directory.contains_key(name) {return Err(DirError::DuplicateName(name.to_owned()));}
+        for name in directory.link.keys() {
+            if self.file.contains_key(name) || self.directory.contains_key(name) || self.link.contains_key(name) {return Err(DirError::DuplicateName(name.to_owned()));}
         }
 
         for (name, dir) in directory.file {
@@ -881,35 +1355,21 @@ impl <T> DirHierarchy<DirMulti<T>> for DirMulti<T> {
             self.insert_dir(name, dir)?;
         }
 
+        self.link.extend(directory.link);
+
         Ok(())
     }
 
     fn crawl(&self) -> Vec<&DirMulti<T>> {
-        let mut vector = Vec::new();
-        for pair in &self.directory{
-            vector.push(pair.1);
-            let mut content = pair.1.crawl();
-            vector.append(&mut content);
-        }
-        vector
+        self.iter_dirs().map(|(_, directory)| directory).collect()
     }
 
     fn tree(&self) -> String {
-        let text = String::new();
-        format!(
-            "> {}{}",
-            self.name.purple().bold().underline(),
-            self.cascade_tree(text, 0, "")
-        )
+        self.tree_format().to_string()
     }
 
     fn tree_dir(&self) -> String {
-        let text = String::new();
-        format!(
-            "> {}{}",
-            self.name.purple().bold().underline(),
-            self.cascade_tree(text, 0, "no-dir")
-        )
+        self.tree_format().files(false).to_string()
     }
 
     fn get_name(&self) -> &String {
@@ -926,7 +1386,8 @@ impl <T> DirHierarchy<DirMulti<T>> for DirMulti<T> {
 }
 impl <T> DirFiles<T> for DirMulti<T> {
     fn add_file(&mut self, name: impl Borrow<str>, file: T) -> Result<(), DirError>{
-        if self.file.contains_key(name.borrow()) == false {
+        validate_name(name.borrow(), name.borrow())?;
+        if !self.file.contains_key(name.borrow()) && !self.link.contains_key(name.borrow()) {
             self.file.insert(name.borrow().to_owned(), file);
             Ok(())
         } else {
@@ -934,33 +1395,74 @@ impl <T> DirFiles<T> for DirMulti<T> {
         }
     }
 
-    fn insert_file(&mut self, path: impl Borrow<str>, file: T) -> Result<(), DirError>{
-        match path.borrow().rsplit_once('/'){
-            None => self.add_file(path, file),
-            Some ((directory_path, name)) => match self.borrow_dir_mut(directory_path) {
-                Ok(borrowed_directory) => borrowed_directory.add_file(name, file),
-                Err(e) => Err(e),
-            }
+    fn insert_file(&mut self, path: impl AsRef<TreePath>, file: T) -> Result<(), DirError>{
+        let (names, name) = resolve_parent(&self.path, path.as_ref())?;
+        match self.descend_mut(&names) {
+            Ok(borrowed_directory) => borrowed_directory.add_file(name, file),
+            Err(e) => Err(e),
+        }
+    }
+
+    fn insert_file_with_parents(&mut self, path: impl AsRef<TreePath>, file: T) -> Result<Vec<String>, DirError>{
+        let (names, name) = resolve_parent(&self.path, path.as_ref())?;
+        if let Ok(directory) = self.descend(&names) {
+            if directory.file.contains_key(name) { return Err(DirError::NameInUse(path.as_ref().to_string())) }
+        }
+        let mut created = Vec::new();
+        match self.ensure_dir_mut(&names, &mut created) {
+            Ok(borrowed_directory) => borrowed_directory.add_file(name, file).map(|_| created),
+            Err(e) => Err(e),
         }
     }
 
     fn take_file(&mut self, name: impl Borrow<str>) -> Result<T, DirError> {
-        match self.file.remove(name.borrow()) {
+        match storage_remove(&mut self.file, name.borrow()) {
             Some(file) => Ok(file),
             None => Err(DirError::NoFile(name.borrow().to_owned())),
         }
     }
 
-    fn remove_file(&mut self, path: impl Borrow<str>) -> Result<T, DirError> {
-        match path.borrow().split_once('/') {
-            None => self.take_file(path),
-            Some((branch, remaining_path)) => match self.borrow_dir_mut(branch) {
-                Ok(borrowed_directory) => borrowed_directory.remove_file(remaining_path),
-                Err(e) => Err(e),
-            },
+    fn remove_file(&mut self, path: impl AsRef<TreePath>) -> Result<T, DirError> {
+        let (names, name) = resolve_parent(&self.path, path.as_ref())?;
+        match self.descend_mut(&names) {
+            Ok(borrowed_directory) => borrowed_directory.take_file(name),
+            Err(e) => Err(e),
         }
     }
 
+    fn rename_file(&mut self, path: impl AsRef<TreePath>, name: impl Borrow<str>) -> Result<String, DirError> {
+        let (names, old_name) = resolve_parent(&self.path, path.as_ref())?;
+        validate_name(name.borrow(), name.borrow())?;
+        let directory = self.descend_mut(&names)?;
+        let new_path = join_names(&names, name.borrow());
+        if !directory.file.contains_key(old_name) { return Err(DirError::NoFile(path.as_ref().to_string())) }
+        if old_name == name.borrow() { return Ok(new_path) }
+        if directory.file.contains_key(name.borrow()) || directory.link.contains_key(name.borrow()) { return Err(DirError::NameInUse(new_path)) }
+        let file = directory.take_file(old_name)?;
+        directory.file.insert(name.borrow().to_owned(), file);
+        Ok(new_path)
+    }
+
+    fn move_file(&mut self, source: impl AsRef<TreePath>, destination: impl AsRef<TreePath>, overwrite: bool) -> Result<String, DirError> {
+        let (source_parent, source_name) = resolve_parent(&self.path, source.as_ref())?;
+        let destination_names = resolve_path(&self.path, destination.as_ref())?;
+        self.descend(&source_parent)?.obtain_file(source_name)?;
+        let (target_parent, target_name) = match self.descend(&destination_names) {
+            Ok(_) => (destination_names.as_slice(), source_name),
+            Err(_) => match destination_names.split_last() {
+                Some((name, parent)) => (parent, *name),
+                None => return Err(DirError::InvalidPath(destination.as_ref().to_string())),
+            },
+        };
+        let target = join_names(target_parent, target_name);
+        if source_parent == target_parent && source_name == target_name { return Ok(target) }
+        if self.descend(target_parent)?.link.contains_key(target_name) { return Err(DirError::NameInUse(target)) }
+        if self.descend(target_parent)?.file.contains_key(target_name) && !overwrite { return Err(DirError::NameInUse(target)) }
+        let file = self.descend_mut(&source_parent)?.take_file(source_name)?;
+        self.descend_mut(target_parent)?.file.insert(target_name.to_owned(), file);
+        Ok(target)
+    }
+
     fn obtain_file(&self, name: impl Borrow<str>) -> Result<&T, DirError> {
         match self.file.get(name.borrow()) {
             Some(file) => Ok(file),
@@ -975,40 +1477,159 @@ impl <T> DirFiles<T> for DirMulti<T> {
         }
     }
 
-    fn borrow_file(&self, path: impl Borrow<str>) -> Result<&T, DirError> {
-        match path.borrow().split_once('/') {
-            None => self.obtain_file(path),
-            Some((branch, remaining_path)) => match self.obtain_dir(branch) {
-                Ok(borrowed_directory) => borrowed_directory.borrow_file(remaining_path),
-                Err(e) => Err(e),
-            },
+    fn borrow_file(&self, path: impl AsRef<TreePath>) -> Result<&T, DirError> {
+        match resolve_parent(&self.path, path.as_ref()) {
+            Ok((names, name)) => self.lookup_file(&names, name),
+            Err(e) => Err(e),
         }
     }
     
-    fn borrow_file_mut(&mut self, path: impl Borrow<str>) -> Result<&mut T, DirError> {
-        match path.borrow().split_once('/') {
-            None => self.obtain_file_mut(path),
-            Some((branch, remaining_path)) => match self.obtain_dir_mut(branch) {
-                Ok(borrowed_directory) => borrowed_directory.borrow_file_mut(remaining_path),
-                Err(e) => Err(e),
-            },
+    fn borrow_file_mut(&mut self, path: impl AsRef<TreePath>) -> Result<&mut T, DirError> {
+        match resolve_parent(&self.path, path.as_ref()) {
+            Ok((names, name)) => self.lookup_file_mut(&names, name),
+            Err(e) => Err(e),
         }
     }
 }
 
+#[cfg(feature = "serde")]
+impl <T:Serialize> DirMulti<T> {
+    /// Number of non-empty data fields written by [`DirMulti::serialize_content`]
+    fn content_len(&self) -> usize {
+        !self.file.is_empty() as usize + !self.directory.is_empty() as usize + !self.link.is_empty() as usize
+    }
+
+    /// Write the data fields into the struct, skipping the empty ones
+    fn serialize_content<S: SerializeStruct>(&self, s: &mut S) -> Result<(), S::Error> {
+        if self.file.is_empty() { s.skip_field("file")? } else { s.serialize_field("file", &self.file)? }
+        if self.directory.is_empty() { s.skip_field("directory")? } else { s.serialize_field("directory", &self.directory)? }
+        if self.link.is_empty() { s.skip_field("link") } else { s.serialize_field("link", &self.link) }
+    }
+}
+#[cfg(feature = "serde")]
+impl <T> DirMulti<T> {
+    /// Build directory from the deserialized representation and recompute cached name, path and depth for the whole subtree
+    fn from_repr(repr: DirRepr<Entries<T>>, name: String, path: String, depth: f32) -> Result<Self, DirError> {
+        let mut directory = DirMulti::new();
+        for (file_name, file) in repr.file.0 {
+            let file_path = if path.is_empty() { file_name.to_owned() } else { path.to_owned() + "/" + &file_name };
+            validate_name(&file_name, &file_path)?;
+            if directory.file.contains_key(&file_name) { return Err(DirError::NameInUse(file_path)); }
+            directory.file.insert(file_name, file);
+        }
+        for (dir_name, dir) in repr.directory.0 {
+            let dir_path = if path.is_empty() { dir_name.to_owned() } else { path.to_owned() + "/" + &dir_name };
+            validate_name(&dir_name, &dir_path)?;
+            if directory.directory.contains_key(&dir_name) { return Err(DirError::NameInUse(dir_path)); }
+            let subdirectory = DirMulti::from_repr(dir, dir_name.to_owned(), dir_path, depth + 1.0)?;
+            directory.directory.insert(dir_name, subdirectory);
+        }
+        for (link_name, target) in repr.link.0 {
+            let link_path = if path.is_empty() { link_name.to_owned() } else { path.to_owned() + "/" + &link_name };
+            validate_name(&link_name, &link_path)?;
+            if directory.link.contains_key(&link_name) || directory.directory.contains_key(&link_name) || directory.file.contains_key(&link_name) { return Err(DirError::NameInUse(link_path)); }
+            directory.link.insert(link_name, target);
+        }
+        directory.name = name;
+        directory.path = path;
+        directory.depth = depth;
+        Ok(directory)
+    }
+}
 #[cfg(feature = "serde")]
 impl <T:Serialize> Serialize for DirMulti<T> {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: Serializer,
     {
-        let mut s = serializer.serialize_struct("DirMulti", 5)?;
-        s.serialize_field("name", &self.name)?;
-        s.serialize_field("path", &self.path)?;
-        s.serialize_field("depth", &self.depth)?;
-        s.serialize_field("file", &self.file)?;
-        s.serialize_field("directory", &self.directory)?;
+        let mut s = serializer.serialize_struct("DirMulti", self.content_len())?;
+        self.serialize_content(&mut s)?;
         s.end()
     }
 }
+#[cfg(feature = "serde")]
+impl <'de, T:Deserialize<'de>> Deserialize<'de> for DirMulti<T> {
+    /// Deserialized directory is detached, so the cached paths of its subtree are relative to itself
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let repr = DirRepr::<Entries<T>>::deserialize(deserializer)?;
+        match DirMulti::from_repr(repr, DirMulti::<T>::new().name, "".to_owned(), 0.0) {
+            Ok(directory) => Ok(directory),
+            Err(e) => Err(de::Error::custom(e)),
+        }
+    }
+}
+
+
+
+// #=============================#
+// #=== SERDE REPRESENTATIONS ===#
+
+
+/// Compact form of a directory. Cached name, path and depth are left out, they are derived from the hierarchy.
+#[cfg(feature = "serde")]
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct DirRepr<F> {
+    #[serde(default)]
+    file: F,
+    #[serde(default)]
+    directory: Entries<DirRepr<F>>,
+    #[serde(default)]
+    link: Entries<TreePathBuf>,
+}
+
+/// Compact form of a DirMap. Same as [`DirRepr`] but carries the root name.
+#[cfg(feature = "serde")]
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct MapRepr<F> {
+    #[serde(default)]
+    name: String,
+    #[serde(default)]
+    file: F,
+    #[serde(default)]
+    directory: Entries<DirRepr<F>>,
+    #[serde(default)]
+    link: Entries<TreePathBuf>,
+}
+
+/// Map deserialized into a list of pairs, so duplicate names are not silently dropped
+#[cfg(feature = "serde")]
+struct Entries<V>(Vec<(String, V)>);
+#[cfg(feature = "serde")]
+impl <V> Default for Entries<V> {
+    fn default() -> Self {
+        Entries(Vec::new())
+    }
+}
+#[cfg(feature = "serde")]
+impl <'de, V:Deserialize<'de>> Deserialize<'de> for Entries<V> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct EntriesVisitor<V>(std::marker::PhantomData<V>);
+        impl <'de, V:Deserialize<'de>> Visitor<'de> for EntriesVisitor<V> {
+            type Value = Entries<V>;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                formatter.write_str("a map of names")
+            }
 
+            fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+            where
+                A: MapAccess<'de>,
+            {
+                let mut entries = Vec::with_capacity(map.size_hint().unwrap_or(0));
+                while let Some(entry) = map.next_entry()? {
+                    entries.push(entry);
+                }
+                Ok(Entries(entries))
+            }
+        }
+        deserializer.deserialize_map(EntriesVisitor(std::marker::PhantomData))
+    }
+}
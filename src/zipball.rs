@@ -0,0 +1,225 @@
+use std::borrow::Borrow;
+use std::io::{Read, Seek, Write};
+use std::path::Path;
+use std::sync::{Mutex, OnceLock, PoisonError};
+
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+use zip::result::ZipError;
+use zip::write::SimpleFileOptions;
+
+use crate::{DirError, DirFiles, DirHierarchy, DirMapMulti, DirMulti, PathTreeInit, TreePath};
+use crate::fs::{Planned, link_target, plan};
+use crate::tree::{join_names, join_path, resolve_parent, resolve_path};
+
+// #===========================#
+// #=== ZIP IMPLEMENTATIONS ===#
+
+/// Convert zip error into [`DirError::Io`] mentioning the archive or entry it happened at
+fn zip_error(name: &str, error: ZipError) -> DirError {
+    DirError::Io(format!("{name}: {error}"))
+}
+
+/// Decompress the entry at the index
+fn read_entry<R: Read + Seek>(archive: &mut ZipArchive<R>, index: usize) -> Result<Vec<u8>, DirError> {
+    let mut file = archive.by_index(index).map_err(|e| zip_error(&index.to_string(), e))?;
+    let mut bytes = Vec::with_capacity(file.size() as usize);
+    match file.read_to_end(&mut bytes) {
+        Ok(_) => Ok(bytes),
+        Err(e) => Err(DirError::Io(format!("{}: {e}", file.name()))),
+    }
+}
+
+
+/// ## Zip compression
+/// Compression used for a single file written by [`DirMulti::write_zip_with`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ZipCompression {
+    /// Store the file as is
+    Stored,
+
+    /// Compress the file with deflate at the default level
+    #[default]
+    Deflated,
+
+    /// Compress the file with deflate at the level from 0 to 9
+    DeflatedLevel (u32),
+}
+impl ZipCompression {
+    /// Convert into options of the written entry
+    fn options(self) -> SimpleFileOptions {
+        let options = SimpleFileOptions::default();
+        match self {
+            ZipCompression::Stored => options.compression_method(CompressionMethod::Stored),
+            ZipCompression::Deflated => options.compression_method(CompressionMethod::Deflated),
+            ZipCompression::DeflatedLevel(level) => options.compression_method(CompressionMethod::Deflated).compression_level(Some(i64::from(level))),
+        }
+    }
+}
+
+
+/// # Zip entry
+/// File of a [`ZipTree`], holds index of the entry in the archive and its content once decompressed.
+#[derive(Debug, Clone)]
+pub struct ZipEntry {
+    index: usize,
+    data: OnceLock<Vec<u8>>,
+}
+impl ZipEntry {
+    /// Returns index of the entry in the archive
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// Returns true if the content was already decompressed
+    pub fn is_loaded(&self) -> bool {
+        self.data.get().is_some()
+    }
+}
+
+
+/// ## Zip tree
+/// Zip archive mirrored into a [`DirMapMulti`] without unpacking it. Only the central directory is read up front,
+/// every file is decompressed on the first borrow and kept in memory afterwards.
+///
+/// Entries ending with `/` become directories, missing parent directories are created. Paths are resolved like any other path
+/// of the tree, so entries can't be placed outside of it, and the last entry of a path wins. Symlink entries with relative targets
+/// become links of the tree, others are skipped.
+/// ```
+/// # use pathio::prelude::*;
+/// # use std::io::Cursor;
+/// let mut tree: PathTree<Vec<u8>> = PathTree::new("mod");
+/// tree.insert_file_with_parents("textures/sword.png", vec![1, 2, 3]).unwrap();
+/// let archive = tree.write_zip(Cursor::new(Vec::new())).unwrap();
+///
+/// let zip = ZipTree::new("mod", archive).unwrap();
+/// assert!(!zip.structure().borrow_file("textures/sword.png").unwrap().is_loaded());
+/// assert_eq!(zip.borrow_file("textures/sword.png").unwrap(), &vec![1, 2, 3]);
+/// assert_eq!(zip.into_tree().unwrap(), tree);
+/// ```
+pub struct ZipTree<R> {
+    archive: Mutex<ZipArchive<R>>,
+    structure: DirMapMulti<ZipEntry>,
+}
+impl <R: Read + Seek> ZipTree<R> {
+    /// Read the central directory of the archive and build the tree with the name.
+    /// A name used both by a file and a directory, like `a` next to `a/b`, fails with [`DirError::AmbiguousPath`].
+    pub fn new(name: impl Borrow<str>, reader: R) -> Result<Self, DirError> {
+        let mut archive = ZipArchive::new(reader).map_err(|e| zip_error(name.borrow(), e))?;
+        let mut structure: DirMapMulti<ZipEntry> = DirMapMulti::new(name);
+        for index in 0..archive.len() {
+            let path = match archive.name_for_index(index) {
+                Some(path) => path.to_owned(),
+                None => continue,
+            };
+            let path = path.as_str();
+            if archive.by_index_raw(index).map_err(|e| zip_error(path, e))?.is_symlink() {
+                let target = String::from_utf8_lossy(&read_entry(&mut archive, index)?).into_owned();
+                if let Some(target) = link_target(Path::new(&target)) {
+                    let (names, name) = resolve_parent(structure.get_path(), TreePath::new(path))?;
+                    let directory = structure.directory.ensure_flat_dir_mut(&names)?;
+                    if directory.directory.contains_key(name) || directory.file.contains_key(name) { return Err(DirError::AmbiguousPath(join_names(&names, name))) }
+                    directory.link.insert(name.to_owned(), target);
+                }
+            } else if path.ends_with('/') {
+                let names = resolve_path(structure.get_path(), TreePath::new(path))?;
+                structure.directory.ensure_flat_dir_mut(&names)?;
+            } else {
+                let (names, name) = resolve_parent(structure.get_path(), TreePath::new(path))?;
+                let directory = structure.directory.ensure_flat_dir_mut(&names)?;
+                if directory.directory.contains_key(name) || directory.link.contains_key(name) { return Err(DirError::AmbiguousPath(join_names(&names, name))) }
+                directory.file.insert(name.to_owned(), ZipEntry { index, data: OnceLock::new() });
+            }
+        }
+        Ok(ZipTree { archive: Mutex::new(archive), structure })
+    }
+
+    /// Borrow the tree of entries, useful for listing, glob or find without decompressing anything
+    pub fn structure(&self) -> &DirMapMulti<ZipEntry> {
+        &self.structure
+    }
+
+    /// Borrow content of the file at the path, decompressing it on the first borrow
+    pub fn borrow_file(&self, path: impl AsRef<TreePath>) -> Result<&Vec<u8>, DirError> {
+        let entry = self.structure.borrow_file(path)?;
+        if let Some(data) = entry.data.get() { return Ok(data) }
+        let bytes = read_entry(&mut self.archive.lock().unwrap_or_else(PoisonError::into_inner), entry.index)?;
+        Ok(entry.data.get_or_init(|| bytes))
+    }
+
+    /// Mutably borrow content of the file at the path, decompressing it on the first borrow. Changes are kept only in memory.
+    pub fn borrow_file_mut(&mut self, path: impl AsRef<TreePath>) -> Result<&mut Vec<u8>, DirError> {
+        let archive = self.archive.get_mut().unwrap_or_else(PoisonError::into_inner);
+        let entry = self.structure.borrow_file_mut(path)?;
+        if entry.data.get().is_none() {
+            let _ = entry.data.set(read_entry(archive, entry.index)?);
+        }
+        match entry.data.get_mut() {
+            Some(data) => Ok(data),
+            None => unreachable!("entry was just loaded"),
+        }
+    }
+
+    /// Decompress every file that wasn't borrowed yet and convert into a regular tree
+    pub fn into_tree(self) -> Result<DirMapMulti<Vec<u8>>, DirError> {
+        let mut archive = self.archive.into_inner().unwrap_or_else(PoisonError::into_inner);
+        let mut tree = DirMapMulti::new(self.structure.get_name().as_str());
+        let mut links = Vec::new();
+        for (path, directory) in self.structure.walk() {
+            if !path.is_empty() { tree.create_dir_all(&path)?; }
+            links.extend(directory.link.iter().map(|(name, target)| (join_path(&path, name), target.to_owned())));
+        }
+        for (path, entry) in self.structure {
+            let data = match entry.data.into_inner() {
+                Some(data) => data,
+                None => read_entry(&mut archive, entry.index)?,
+            };
+            tree.insert_file_with_parents(path, data)?;
+        }
+        for (path, target) in links {
+            tree.create_link(path, target)?;
+        }
+        Ok(tree)
+    }
+}
+
+
+// #===========================#
+// #=== DIR IMPLEMENTATIONS ===#
+
+impl <T: AsRef<[u8]>> DirMulti<T> {
+    /// Write the directory as a zip archive with paths relative to it, every file is deflated. Returns the writer.
+    pub fn write_zip<W: Write + Seek>(&self, writer: W) -> Result<W, DirError> {
+        self.write_zip_with(writer, |_, _| ZipCompression::Deflated)
+    }
+
+    /// Write the directory as a zip archive, the callback picks compression of every file from its path and content. Returns the writer.
+    ///
+    /// Empty directories are kept as directory entries and links become symlink entries, absolute links pointing outside of the directory are skipped.
+    pub fn write_zip_with<W: Write + Seek>(&self, writer: W, compression: impl Fn(&str, &T) -> ZipCompression) -> Result<W, DirError> {
+        let mut planned = Vec::new();
+        plan(self, self.get_path(), "", 0, true, &mut planned, &mut Vec::new());
+        let mut zip = ZipWriter::new(writer);
+        for (path, entry) in planned {
+            match entry {
+                Planned::Dir => zip.add_directory(path.as_str(), SimpleFileOptions::default()).map_err(|e| zip_error(&path, e))?,
+                Planned::File(file) => {
+                    zip.start_file(path.as_str(), compression(&path, file).options()).map_err(|e| zip_error(&path, e))?;
+                    zip.write_all(file.as_ref()).map_err(|e| DirError::Io(format!("{path}: {e}")))?;
+                },
+                Planned::Link(target) => zip.add_symlink(path.as_str(), target, SimpleFileOptions::default()).map_err(|e| zip_error(&path, e))?,
+            }
+        }
+        zip.finish().map_err(|e| zip_error(self.get_name(), e))
+    }
+}
+impl <T: AsRef<[u8]>> DirMapMulti<T> {
+    /// Write the tree as a zip archive, see [`DirMulti::write_zip`]
+    pub fn write_zip<W: Write + Seek>(&self, writer: W) -> Result<W, DirError> {
+        self.directory.write_zip(writer)
+    }
+
+    /// Write the tree as a zip archive with compression picked per file, see [`DirMulti::write_zip_with`]
+    pub fn write_zip_with<W: Write + Seek>(&self, writer: W, compression: impl Fn(&str, &T) -> ZipCompression) -> Result<W, DirError> {
+        self.directory.write_zip_with(writer, compression)
+    }
+}